@@ -110,6 +110,12 @@ rec {
             name = "indoc";
             packageId = "indoc";
           }
+          {
+            name = "pulldown-cmark";
+            packageId = "pulldown-cmark";
+            usesDefaultFeatures = false;
+            features = [ "html" ];
+          }
           {
             name = "rusqlite";
             packageId = "rusqlite";
@@ -986,6 +992,25 @@ rec {
         ];
 
       };
+      "memchr" = rec {
+        crateName = "memchr";
+        version = "2.8.3";
+        edition = "2021";
+        sha256 = "161xa63ipfanf8v3nb82xd5hqgydv55nzw59wyngqbz6alfaz2yg";
+        authors = [
+          "Andrew Gallant <jamslam@gmail.com>"
+          "bluss"
+        ];
+        features = {
+          "core" = [ "dep:core" ];
+          "default" = [ "std" ];
+          "logging" = [ "dep:log" ];
+          "rustc-dep-of-std" = [ "core" ];
+          "std" = [ "alloc" ];
+          "use_std" = [ "std" ];
+        };
+        resolvedDefaultFeatures = [ "alloc" "default" "std" ];
+      };
       "new_debug_unreachable" = rec {
         crateName = "new_debug_unreachable";
         version = "1.0.6";
@@ -1387,6 +1412,59 @@ rec {
         };
         resolvedDefaultFeatures = [ "default" "proc-macro" ];
       };
+      "pulldown-cmark" = rec {
+        crateName = "pulldown-cmark";
+        version = "0.13.4";
+        edition = "2021";
+        crateBin = [];
+        sha256 = "0kii5zdm7nvdjh7rjkjpvxd0sx1cyd21p0qijmgiq1z7m3mniw79";
+        libName = "pulldown_cmark";
+        authors = [
+          "Raph Levien <raph.levien@gmail.com>"
+          "Marcus Klaas de Vries <mail@marcusklaas.nl>"
+        ];
+        dependencies = [
+          {
+            name = "bitflags";
+            packageId = "bitflags 2.5.0";
+          }
+          {
+            name = "memchr";
+            packageId = "memchr";
+          }
+          {
+            name = "pulldown-cmark-escape";
+            packageId = "pulldown-cmark-escape";
+            optional = true;
+          }
+          {
+            name = "unicase";
+            packageId = "unicase";
+          }
+        ];
+        features = {
+          "default" = [ "getopts" "html" ];
+          "getopts" = [ "dep:getopts" ];
+          "html" = [ "pulldown-cmark-escape" ];
+          "pulldown-cmark-escape" = [ "dep:pulldown-cmark-escape" ];
+          "serde" = [ "dep:serde" ];
+          "simd" = [ "pulldown-cmark-escape?/simd" ];
+        };
+        resolvedDefaultFeatures = [ "html" "pulldown-cmark-escape" ];
+      };
+      "pulldown-cmark-escape" = rec {
+        crateName = "pulldown-cmark-escape";
+        version = "0.11.0";
+        edition = "2021";
+        sha256 = "1bp13akkz52p43vh2ffpgv604l3xd9b67b4iykizidnsbpdqlz80";
+        libName = "pulldown_cmark_escape";
+        authors = [
+          "Raph Levien <raph.levien@gmail.com>"
+          "Marcus Klaas de Vries <mail@marcusklaas.nl>"
+        ];
+        features = {
+        };
+      };
       "quote" = rec {
         crateName = "quote";
         version = "1.0.36";
@@ -2126,6 +2204,17 @@ rec {
         ];
 
       };
+      "unicase" = rec {
+        crateName = "unicase";
+        version = "2.10.0";
+        edition = "2018";
+        sha256 = "13x2h9s4jhch7rknqkqb1pb35irpj00fswy9ssgh0dm0qsnc6z1m";
+        authors = [
+          "Sean McArthur <sean@seanmonstar.com>"
+        ];
+        features = {
+        };
+      };
       "unicode-bidi" = rec {
         crateName = "unicode-bidi";
         version = "0.3.15";
@@ -2,6 +2,8 @@ use std::{path::PathBuf, str::FromStr};
 
 use argh::FromArgs;
 
+use crate::{module::ItemKind, pipeline::LocalDefinitions};
+
 #[derive(Debug, FromArgs)]
 /// Index top-level definitions found in Agda modules rendered to HTML
 pub struct CommandLine {
@@ -9,21 +11,137 @@ pub struct CommandLine {
     /// textual format of the finished index.
     /// Either
     /// "plain" (space-separated plaintext, default),
-    /// "json" (JSON dictionary {{<source file>: <module items>}}), or
-    /// "docset" (a Dash Docset)
+    /// "json" (JSON dictionary {{<source file>: <module items>}}),
+    /// "docset" (a Dash Docset),
+    /// "dot" (Graphviz graph of module imports), or
+    /// "editor" (like "plain", but with <source file>:<line>:<column> locations)
     pub output_format: OutputFormat,
 
-    #[argh(option, default = r#"("agda".into())"#)]
+    #[argh(option, default = r#"".".into()"#)]
+    /// directory containing the Agda source files, for "editor" output (default: .)
+    pub source_root: PathBuf,
+
+    #[argh(switch)]
+    /// group modules by their namespace in "dot" output
+    pub cluster: bool,
+
+    #[argh(switch)]
+    /// list constructors under their data type in "json" output
+    pub nest_constructors: bool,
+
+    #[argh(switch)]
+    /// include the source of each definition in "json" output
+    pub snippets: bool,
+
+    #[argh(option, default = r#""agda".into()"#)]
     /// name of the Agda library (field `name` in .agda-lib)
     pub library_name: String,
 
-    #[argh(option, default = r#"("index.html".into())"#)]
+    #[argh(option, default = r#""index.html".into()"#)]
     /// path to the main page, relative to <html_dir> (default: index.html)
     pub main_page: PathBuf,
 
+    #[argh(option)]
+    /// only index definitions of this kind (repeatable). One of
+    /// "module", "function", "datatype", "constructor", "record", "field",
+    /// "postulate", "primitive", "macro", "pattern-synonym" or "generalizable"
+    pub kind: Vec<ItemKind>,
+
+    #[argh(switch)]
+    /// do not index definitions declared in `private` blocks
+    pub exclude_private: bool,
+
+    #[argh(switch)]
+    /// do not index definitions deprecated by `WARNING_ON_USAGE` pragmas
+    pub exclude_deprecated: bool,
+
+    #[argh(option, default = "LocalDefinitions::Keep")]
+    /// how to index definitions local to a `where` block. Either
+    /// "keep" (like top-level definitions, default),
+    /// "hide" (leave them out), or
+    /// "qualify" (qualified by their parent definition, as in "parent.helper")
+    pub local_definitions: LocalDefinitions,
+
+    #[argh(option)]
+    /// only index modules whose OPTIONS pragmas enable this flag, like
+    /// "--without-K" (repeatable). Flags implied by others count as well.
+    /// Modules using infective options like "--cubical" that are not given are left out
+    pub with_option: Vec<String>,
+
     #[argh(positional)]
-    /// paths to directory containing HTML files of rendered Agda modules
+    /// paths to directory containing HTML (or Markdown) files of rendered Agda modules
     pub html_dir: PathBuf,
+
+    #[argh(subcommand)]
+    /// query the index instead of writing it
+    pub query: Option<Query>,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand)]
+pub enum Query {
+    Uses(UsesQuery),
+    Instances(InstancesQuery),
+    Builtins(BuiltinsQuery),
+    Audit(AuditQuery),
+    Diagnostics(DiagnosticsQuery),
+    Show(ShowQuery),
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "uses")]
+/// List definitions referring to a given definition
+pub struct UsesQuery {
+    #[argh(positional)]
+    /// name of the definition, either fully qualified or unqualified
+    pub name: String,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "instances")]
+/// List instances of a given record type
+pub struct InstancesQuery {
+    #[argh(positional)]
+    /// name of the record type, either fully qualified or unqualified
+    pub name: String,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "builtins")]
+/// List the definitions bound to Agda builtins by BUILTIN pragmas
+pub struct BuiltinsQuery {}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "audit")]
+/// List postulates, pragmas turning off checks and modules lacking --safe,
+/// in all modules regardless of the filtering options
+pub struct AuditQuery {
+    #[argh(switch)]
+    /// write the report as JSON instead of plaintext
+    pub json: bool,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "diagnostics")]
+/// List problems Agda highlighted, like unsolved metas, and fail if there are any,
+/// in all modules regardless of the filtering options
+pub struct DiagnosticsQuery {
+    #[argh(switch)]
+    /// write the problems as JSON instead of plaintext
+    pub json: bool,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "show")]
+/// Print the source of a definition, from its type signature through its clauses
+pub struct ShowQuery {
+    #[argh(positional)]
+    /// fully qualified name of the definition
+    pub name: String,
+
+    #[argh(switch)]
+    /// print the source as highlighted HTML instead of plaintext
+    pub html: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -31,6 +149,8 @@ pub enum OutputFormat {
     Plain,
     Json,
     Docset,
+    Dot,
+    Editor,
 }
 
 impl FromStr for OutputFormat {
@@ -41,7 +161,9 @@ impl FromStr for OutputFormat {
             "plain" => Ok(Self::Plain),
             "json" => Ok(Self::Json),
             "docset" => Ok(Self::Docset),
-            _ => Err("expected one of 'plain', 'json' or 'docset'"),
+            "dot" => Ok(Self::Dot),
+            "editor" => Ok(Self::Editor),
+            _ => Err("expected one of 'plain', 'json', 'docset', 'dot' or 'editor'"),
         }
     }
 }
@@ -8,30 +8,59 @@ mod output;
 mod pipeline;
 
 use anyhow::Result;
-use cmdline::{CommandLine, OutputFormat};
+use cmdline::{CommandLine, OutputFormat, Query};
 
+use crate::output::AuditOutput;
+use crate::output::BuiltinsOutput;
+use crate::output::DiagnosticsOutput;
 use crate::output::DocsetOutput;
+use crate::output::DotOutput;
+use crate::output::EditorOutput;
+use crate::output::InstancesOutput;
 use crate::output::JsonOutput;
-use crate::output::{OutputWriter, PlainOutput};
-use crate::pipeline::Pipeline;
-
-#[derive(Debug, PartialEq, Eq)]
-struct ItemInfo {
-    id: String,
-    module: String,
-}
+use crate::output::UsesOutput;
+use crate::output::{OutputWriter, PlainOutput, ShowOutput};
+use crate::pipeline::{Filter, Pipeline};
 
 fn get_output_writer(cmdline: &CommandLine) -> Result<Box<dyn OutputWriter>> {
     let stdout = std::io::stdout();
     let stdout = stdout.lock();
 
+    match &cmdline.query {
+        Some(Query::Uses(query)) => {
+            let uses = UsesOutput::new(query.name.clone(), stdout);
+            return Ok(Box::new(uses));
+        }
+        Some(Query::Instances(query)) => {
+            let instances = InstancesOutput::new(query.name.clone(), stdout);
+            return Ok(Box::new(instances));
+        }
+        Some(Query::Builtins(_)) => {
+            let builtins = BuiltinsOutput::new(stdout);
+            return Ok(Box::new(builtins));
+        }
+        Some(Query::Audit(query)) => {
+            let audit = AuditOutput::new(stdout, query.json);
+            return Ok(Box::new(audit));
+        }
+        Some(Query::Diagnostics(query)) => {
+            let diagnostics = DiagnosticsOutput::new(stdout, query.json);
+            return Ok(Box::new(diagnostics));
+        }
+        Some(Query::Show(query)) => {
+            let show = ShowOutput::new(query.name.clone(), query.html, stdout);
+            return Ok(Box::new(show));
+        }
+        None => {}
+    }
+
     match cmdline.output_format {
         OutputFormat::Plain => {
             let plain = PlainOutput::new(stdout);
             Ok(Box::new(plain))
         }
         OutputFormat::Json => {
-            let json = JsonOutput::new(stdout);
+            let json = JsonOutput::new(stdout, cmdline.nest_constructors);
             Ok(Box::new(json))
         }
         OutputFormat::Docset => {
@@ -43,6 +72,14 @@ fn get_output_writer(cmdline: &CommandLine) -> Result<Box<dyn OutputWriter>> {
             );
             Ok(Box::new(docset))
         }
+        OutputFormat::Dot => {
+            let dot = DotOutput::new(stdout, cmdline.cluster);
+            Ok(Box::new(dot))
+        }
+        OutputFormat::Editor => {
+            let editor = EditorOutput::new(stdout, cmdline.source_root.clone());
+            Ok(Box::new(editor))
+        }
     }
 }
 
@@ -52,7 +89,20 @@ fn main() -> Result<()> {
     let mut output = get_output_writer(&cmdline)?;
 
     let pipeline = {
-        let pipeline = Pipeline::new();
+        let filter = match cmdline.query {
+            // Audits and diagnostics cover every module and definition, however
+            // the index would be narrowed down.
+            Some(Query::Audit(_) | Query::Diagnostics(_)) => Filter::default(),
+            _ => Filter {
+                kinds: cmdline.kind.clone(),
+                exclude_private: cmdline.exclude_private,
+                exclude_deprecated: cmdline.exclude_deprecated,
+                local_definitions: cmdline.local_definitions,
+                options: cmdline.with_option.clone(),
+            },
+        };
+        let pipeline = Pipeline::new(filter)
+            .with_snippets(cmdline.snippets || matches!(cmdline.query, Some(Query::Show(_))));
 
         let html_dir = &cmdline.html_dir;
 
@@ -61,10 +111,26 @@ fn main() -> Result<()> {
         module_paths.into_iter().for_each(|entry| match entry {
             Ok(entry) => {
                 let path = entry.path();
-                if path.extension().is_some_and(|ext| ext == "html") {
+                if let Some(Query::Show(query)) = &cmdline.query {
+                    // Only the module declaring a definition needs to be parsed to show it.
+                    let declares = path.file_stem().is_some_and(|stem| {
+                        query
+                            .name
+                            .strip_prefix(&*stem.to_string_lossy())
+                            .is_some_and(|name| name.starts_with('.'))
+                    });
+                    if !declares {
+                        return;
+                    }
+                }
+
+                if path
+                    .extension()
+                    .is_some_and(|ext| ext == "html" || ext == "md")
+                {
                     pipeline.process_module(path);
                 } else {
-                    eprintln!("Skipping non-HTML file {}", path.display());
+                    eprintln!("Skipping non-HTML/Markdown file {}", path.display());
                 }
             }
 
@@ -0,0 +1,174 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Highlighted modules for tests, rendered like `agda --html` would.
+
+use std::collections::HashMap;
+
+const KEYWORDS: &[&str] = &[
+    "abstract",
+    "data",
+    "field",
+    "hiding",
+    "import",
+    "in",
+    "infix",
+    "infixl",
+    "infixr",
+    "instance",
+    "let",
+    "macro",
+    "module",
+    "open",
+    "pattern",
+    "postulate",
+    "primitive",
+    "private",
+    "public",
+    "record",
+    "renaming",
+    "to",
+    "using",
+    "variable",
+    "where",
+    "with",
+];
+
+const SYMBOLS: &[&str] = &[
+    "{-#", "#-}", "(", ")", "{", "}", ":", "=", "→", "λ", "∀", ";", "|", "...",
+];
+
+/// Render the Agda module `source` to a page like `agda --html` does.
+///
+/// `names` lists the highlighting class of each name the module uses. The
+/// type signature of a name, or else its first occurrence, defines it. Other
+/// occurrences link back to it, as do the uses of infix operators. Classes like `Function @Other.html#1`
+/// link elsewhere instead, or nowhere for `Function @`.
+/// Upper case words like `BUILTIN` are pragma keywords, and `Set` is Agda's.
+pub fn render(module: &str, source: &str, names: &[(&str, &str)]) -> String {
+    let classes: HashMap<&str, &str> = names.iter().copied().collect();
+    let tokens = tokenize(source);
+
+    // Names are defined by their type signature, or else where they first occur.
+    let mut definitions: HashMap<&str, usize> = HashMap::new();
+    for (position, &(id, text)) in tokens.iter().enumerate() {
+        let signature = tokens[position + 1..]
+            .iter()
+            .find(|(_, text)| !text.trim().is_empty())
+            .is_some_and(|&(_, text)| text == ":");
+        if signature {
+            definitions.entry(text).or_insert(id);
+        }
+    }
+
+    let mut html = String::new();
+    for &(id, text) in &tokens {
+        let escaped = text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;");
+        let (class, href) = if text.trim().is_empty() {
+            html.push_str(&escaped);
+            continue;
+        } else if text.starts_with("--") || text.starts_with("{-") && text != "{-#" {
+            ("Comment", None)
+        } else if text.starts_with('"') {
+            ("String", None)
+        } else if SYMBOLS.contains(&text) {
+            ("Symbol", None)
+        } else if KEYWORDS.contains(&text) || is_pragma_keyword(text) {
+            ("Keyword", None)
+        } else if text == "Set" {
+            ("PrimitiveType", Some("Agda.Primitive.html#388".to_string()))
+        } else if text.chars().all(|c| c.is_ascii_digit()) {
+            ("Number", None)
+        } else if text == module {
+            ("Module", Some(format!("{module}.html")))
+        } else {
+            // Parts of infix operators like `+` in `m + n` refer to the operator.
+            let operator = format!("_{text}_");
+            let (name, class) = classes
+                .get_key_value(text)
+                .or_else(|| classes.get_key_value(operator.as_str()))
+                .unwrap_or_else(|| panic!("No highlighting class for {text:?}"));
+            match class.split_once(" @") {
+                Some((class, "")) => (class, None),
+                Some((class, target)) => (class, Some(target.to_string())),
+                None => {
+                    let definition = *definitions.entry(name).or_insert(id);
+                    (*class, Some(format!("{module}.html#{definition}")))
+                }
+            }
+        };
+
+        match href {
+            Some(href) => html.push_str(&format!(
+                r#"<a id="{id}" href="{href}" class="{class}">{escaped}</a>"#
+            )),
+            None => html.push_str(&format!(r#"<a id="{id}" class="{class}">{escaped}</a>"#)),
+        }
+    }
+
+    format!(
+        r#"<!DOCTYPE HTML>
+<html><head><meta charset="utf-8"><title>{module}</title><link rel="stylesheet" href="Agda.css"></head><body><pre class="Agda">{html}</pre></body></html>"#
+    )
+}
+
+/// Split `source` into tokens, with the offsets `agda --html` uses as their IDs.
+fn tokenize(source: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut offset = 1;
+
+    let mut rest = source;
+    while !rest.is_empty() {
+        let (text, after) = rest.split_at(token_length(rest));
+        tokens.push((offset, text));
+        offset += text.chars().count();
+        rest = after;
+    }
+
+    tokens
+}
+
+/// Whether `text` is a pragma keyword like `BUILTIN` or `NATURAL`.
+fn is_pragma_keyword(text: &str) -> bool {
+    text.chars().any(|c| c.is_ascii_uppercase())
+        && text.chars().all(|c| c.is_ascii_uppercase() || c == '_')
+}
+
+/// Length in bytes of the token `source` starts with.
+fn token_length(source: &str) -> usize {
+    let end_of = |pattern: &str, from: usize| {
+        source[from..]
+            .find(pattern)
+            .map_or(source.len(), |end| from + end + pattern.len())
+    };
+
+    if source.starts_with(char::is_whitespace) {
+        return source
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(source.len());
+    }
+    if source.starts_with("--") {
+        return source.find('\n').unwrap_or(source.len());
+    }
+    if source.starts_with("{-#") || source.starts_with("#-}") {
+        return 3;
+    }
+    if source.starts_with("{-") {
+        return end_of("-}", 2);
+    }
+    if source.starts_with('"') {
+        return end_of("\"", 1);
+    }
+    if source.starts_with(['(', ')', '{', '}', ';']) {
+        return 1;
+    }
+
+    source
+        .find(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '{' | '}' | ';'))
+        .unwrap_or(source.len())
+}
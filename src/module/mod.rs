@@ -0,0 +1,1689 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+#[cfg(test)]
+pub(crate) mod fixtures;
+mod names;
+mod scopes;
+mod tokens;
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    path::Path,
+    str::FromStr,
+};
+
+use anyhow::{Context, Result};
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+use url::{self, Url};
+
+use self::{
+    scopes::Scopes,
+    tokens::{Token, Tokens},
+};
+
+pub use self::names::transliterate;
+
+#[derive(Debug)]
+pub struct ModuleParser {
+    items: Selector,
+    title: Selector,
+    code: Selector,
+    base_url: Url,
+    snippets: bool,
+}
+
+impl ModuleParser {
+    pub fn new() -> ModuleParser {
+        const ITEM_SELECTOR: &str = r"
+            .Agda .Function ,
+            .Agda .Datatype ,
+            .Agda .InductiveConstructor ,
+            .Agda .CoinductiveConstructor ,
+            .Agda .Record ,
+            .Agda .Field ,
+            .Agda .Postulate ,
+            .Agda .Primitive ,
+            .Agda .PrimitiveType ,
+            .Agda .Macro ,
+            .Agda .Generalizable ,
+            .Agda .Module
+            ";
+        ModuleParser {
+            items: Selector::parse(ITEM_SELECTOR).expect("item selector"),
+            title: Selector::parse("html title").expect("title selector"),
+            code: Selector::parse("pre.Agda").expect("code selector"),
+            base_url: Url::parse("http://invalid./").expect("arbitrary base URL"),
+            snippets: false,
+        }
+    }
+
+    /// Also extract the source of each definition, see [`Item::snippet`].
+    pub fn with_snippets(mut self, snippets: bool) -> Self {
+        self.snippets = snippets;
+        self
+    }
+
+    // pub fn from_path(path: &Path) -> Option<ModuleParser> {
+    //     let base_path = path.parent()?;
+
+    //     let base_url = Url::parse(&format!("file://{}/", base_path.display())).ok()?;
+
+    //     Some(ModuleParser::new(base_url))
+    // }
+
+    fn parse_target_item(&self, target: &Url) -> Result<(String, String)> {
+        let id = target.fragment().context("No target ID")?;
+        let path = self
+            .base_url
+            .make_relative(target)
+            .context("Not a valid URL relative to base URL")?;
+
+        let module = Path::new(&path)
+            .file_stem()
+            .context("No file stem found")?
+            .to_str()
+            .context("File name is not a valid module name")?;
+
+        Ok((id.into(), module.into()))
+    }
+
+    /// Resolve a link to the definition it points at.
+    fn resolve(&self, href: &str, url_parser: url::ParseOptions) -> Option<Reference> {
+        let target = url_parser.parse(href).ok()?;
+        let (id, module) = self.parse_target_item(&target).ok()?;
+        Some(Reference { module, id })
+    }
+
+    /// Extract the type signature following the defining occurrence at `index`.
+    ///
+    /// This is the text after the first `:` that is not nested in brackets,
+    /// up to the end of the declaration or a `where` or `=` that ends it early.
+    fn parse_signature(tokens: &Tokens, index: usize) -> Option<String> {
+        let signature: String = Self::signature_tokens(tokens, index)?
+            .into_iter()
+            .filter(|token| !token.has_class("Comment"))
+            .map(|token| token.text.as_str())
+            .collect();
+
+        Some(signature.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|signature| !signature.is_empty())
+    }
+
+    /// Tokens of the type signature of the declaration at `index`, as for [`Self::parse_signature`].
+    fn signature_tokens<'t, 'doc>(
+        tokens: &'t Tokens<'doc>,
+        index: usize,
+    ) -> Option<Vec<&'t Token<'doc>>> {
+        let mut depth = 0;
+        let mut signature = None;
+
+        for token in tokens.declaration_after(index) {
+            depth += token.nesting();
+
+            if depth < 0
+                || (depth == 0
+                    && (token.is_keyword("where") || token.is_symbol("=") || token.is_symbol(";")))
+            {
+                break;
+            }
+
+            match signature.as_mut() {
+                None if depth == 0 && token.is_symbol(":") => signature = Some(Vec::new()),
+                None => {}
+                Some(signature) => signature.push(token),
+            }
+        }
+
+        signature
+    }
+
+    /// Resolve the type the instance at `index` provides, like `RawMonad` in
+    ///
+    ///     monad : ∀ {a} → RawMonad {a} Maybe
+    ///
+    /// This is the first definition linked to after the last arrow that is not nested
+    /// in brackets or part of a lambda.
+    fn parse_instance_class(
+        &self,
+        tokens: &Tokens,
+        index: usize,
+        url_parser: url::ParseOptions,
+    ) -> Option<Reference> {
+        let signature = Self::signature_tokens(tokens, index)?;
+
+        let mut depth = 0;
+        let mut codomain = 0;
+        for (position, token) in signature.iter().enumerate() {
+            depth += token.nesting();
+            if depth == 0 {
+                if token.is_symbol("λ") || token.is_symbol("\\") {
+                    // Arrows from here on belong to the lambda, not the type.
+                    break;
+                }
+                if token.is_symbol("→") || token.is_symbol("->") {
+                    codomain = position + 1;
+                }
+            }
+        }
+
+        let head = signature[codomain..]
+            .iter()
+            .find(|token| token.href.is_some())
+            .filter(|token| !token.has_class("Bound"))?;
+        self.resolve(head.href?, url_parser)
+    }
+
+    /// Extract the comments directly preceding the declaration at `index`.
+    ///
+    /// Only comments on lines of their own count, and a blank line ends the block.
+    fn parse_doc(tokens: &Tokens, index: usize) -> Option<String> {
+        let mut comments = Vec::new();
+
+        for (index, token) in tokens.before(tokens.line_start(index)) {
+            if token.is_whitespace() {
+                if token.text.matches('\n').count() > 1 {
+                    break;
+                }
+            } else if token.has_class("Comment") && tokens.line_start(index) == index {
+                comments.push(token.text.as_str());
+            } else {
+                break;
+            }
+        }
+
+        comment_text(comments.into_iter().rev())
+    }
+
+    /// Extract a summary of the module from its leading comment block or,
+    /// in literate modules, from the first paragraph of prose.
+    ///
+    /// Pragmas like `{-# OPTIONS … #-}` may precede the comment block,
+    /// which has to come before the `module` declaration.
+    fn parse_summary(tokens: &Tokens) -> Option<String> {
+        let mut comments = Vec::new();
+
+        for (_, token) in tokens.iter() {
+            if token.is_keyword("module") {
+                break;
+            }
+            if token.has_class("Background") {
+                if !comments.is_empty() {
+                    break;
+                }
+                if let Some(paragraph) = first_paragraph(&token.text) {
+                    return Some(paragraph);
+                }
+            } else if token.has_class("Comment") {
+                comments.push(token.text.as_str());
+            } else if token.is_whitespace() {
+                if token.text.matches('\n').count() > 1 && !comments.is_empty() {
+                    break;
+                }
+            } else if !token.has_class("Markup") && !comments.is_empty() {
+                break;
+            }
+        }
+
+        comment_text(comments.into_iter())
+    }
+
+    /// The first paragraph of the prose before the first code block of a module
+    /// rendered to Markdown.
+    fn parse_prose(&self, document: &Html) -> Option<String> {
+        let block = document.select(&self.code).next()?;
+        let mut prose: Vec<String> = block
+            .prev_siblings()
+            .map(|sibling| match ElementRef::wrap(sibling) {
+                Some(element) => element.text().collect(),
+                None => sibling
+                    .value()
+                    .as_text()
+                    .map_or_else(String::new, |text| text.to_string()),
+            })
+            .collect();
+        prose.reverse();
+
+        first_paragraph(strip_front_matter(prose.concat().trim_start()))
+    }
+
+    /// Whether the renaming at `index` exports the new name from the module.
+    ///
+    /// Names renamed by `open` or `import` statements only are exported with
+    /// `public`, while a module `module N = M renaming (X to Y)` exports `N.Y`.
+    fn is_exported_alias(tokens: &Tokens, index: usize) -> bool {
+        let statement = tokens
+            .before(index)
+            .filter(|(_, token)| {
+                token.is_keyword("open") || token.is_keyword("import") || token.is_keyword("module")
+            })
+            .map(|(start, _)| tokens.line_start(start))
+            .find(|&start| start + tokens.declaration_after(start).count() >= index);
+        let Some(start) = statement else {
+            return false;
+        };
+
+        tokens
+            .get(start)
+            .is_some_and(|token| token.is_keyword("module"))
+            || tokens
+                .declaration_after(start)
+                .any(|token| token.is_keyword("public"))
+    }
+
+    /// Resolve the renamed definition `X` for the item `Y` at `index` in `X to Y`.
+    fn parse_alias(
+        &self,
+        tokens: &Tokens,
+        index: usize,
+        url_parser: url::ParseOptions,
+    ) -> Option<AliasTarget> {
+        let (to, _) = tokens
+            .previous(index)
+            .filter(|(_, token)| token.is_keyword("to"))?;
+        let (_, original) = tokens.previous(to)?;
+
+        let target = self.resolve(original.href?, url_parser)?;
+
+        Some(AliasTarget {
+            name: format!("{}.{}", target.module, original.text),
+            target,
+        })
+    }
+
+    fn parse_item(
+        &self,
+        item: ElementRef,
+        tokens: &Tokens,
+        scopes: &Scopes,
+        module_name: &str,
+        url_parser: url::ParseOptions,
+    ) -> Result<Option<Item>> {
+        let identifier = item.text().next().context("Missing text")?;
+        let element = item.value();
+        let id = element.id().context("Missing ID")?;
+
+        let position = tokens.position(id);
+        let mut alias_of = None;
+
+        match element.attr("href") {
+            Some(href) => {
+                // Links to whole modules never point at a definition.
+                let is_definition = self
+                    .resolve(href, url_parser)
+                    .is_some_and(|target| target.id == id && target.module == module_name);
+                if !is_definition {
+                    return Ok(None);
+                }
+            }
+            None => {
+                // Some items are anchors but do not point anywhere.
+                // Assume that these are definitions like `Y` in
+                //
+                //      import Foo.Bar renaming (X to Y)
+                //
+                // and return them as aliases of the definition they rename.
+                // Names the module does not export are no definitions of it at all.
+                if position.is_some_and(|index| !Self::is_exported_alias(tokens, index)) {
+                    return Ok(None);
+                }
+                alias_of = position.and_then(|index| self.parse_alias(tokens, index, url_parser));
+            }
+        }
+
+        if identifier == "_" {
+            // Anonymous modules only contribute their contents.
+            return Ok(None);
+        }
+
+        let classes: Vec<String> = element.classes().map(String::from).collect();
+        let signature = position.and_then(|index| Self::parse_signature(tokens, index));
+        let doc = position
+            .filter(|_| alias_of.is_none())
+            .and_then(|index| Self::parse_doc(tokens, index));
+        let scope = position.map_or_else(Vec::new, |index| scopes.enclosing(index));
+        let visibility = match position {
+            Some(index) if scopes.is_private(index) => Visibility::Private,
+            _ => Visibility::Public,
+        };
+        let is_abstract = position.is_some_and(|index| scopes.is_abstract(index));
+        let snippet = position
+            .filter(|_| self.snippets && alias_of.is_none())
+            .and_then(|index| self.parse_snippet(tokens, index, module_name, url_parser));
+        let is_instance = position.is_some_and(|index| scopes.is_instance(index));
+        let instance_of = position
+            .filter(|_| is_instance)
+            .and_then(|index| self.parse_instance_class(tokens, index, url_parser));
+        let source_position = position
+            .and_then(|index| tokens.get(index))
+            .map(|token| Position {
+                line: token.line,
+                column: token.column,
+            });
+
+        let is_pattern_synonym = position
+            .and_then(|index| tokens.previous(index))
+            .is_some_and(|(_, token)| token.is_keyword("pattern"));
+        let kind = if is_pattern_synonym {
+            ItemKind::PatternSynonym
+        } else {
+            ItemKind::from_classes(&classes).context("Unknown kind of item")?
+        };
+
+        // Constructors and fields belong to the data type or record declared around them.
+        // Its name links to the type signature if that is declared separately.
+        let owner = position
+            .filter(|_| matches!(kind, ItemKind::Constructor | ItemKind::Field))
+            .and_then(|index| scopes.declaring(index))
+            .and_then(|name| tokens.get(name)?.href)
+            .and_then(|href| self.resolve(href, url_parser))
+            .filter(|target| target.module == module_name)
+            .map(|target| target.id);
+
+        Ok(Some(Item {
+            id: id.into(),
+            identifier: identifier.into(),
+            aliases: names::mixfix_aliases(identifier),
+            scope,
+            kind,
+            visibility,
+            is_abstract,
+            is_instance,
+            instance_of,
+            position: source_position,
+            classes,
+            signature,
+            doc,
+            alias_of,
+            parent: None,
+            owner,
+            fixity: None,
+            deprecation: None,
+            snippet,
+            references: Vec::new(),
+        }))
+    }
+
+    /// Extract the source of the declaration at `index`, from its type signature through its clauses.
+    ///
+    /// Clauses follow the signature at the same column without blank lines in between,
+    /// and mention the defined item before their `=` or `with`, or continue a `with`
+    /// clause by `...`. Type signatures of other items and pragmas end the declaration.
+    fn parse_snippet(
+        &self,
+        tokens: &Tokens,
+        index: usize,
+        module_name: &str,
+        url_parser: url::ParseOptions,
+    ) -> Option<Snippet> {
+        let id = tokens.get(index)?.id?;
+        let start = tokens.line_start(index);
+        let column = tokens.get(start)?.column;
+        let declaration_end = |start: usize| start + 1 + tokens.declaration_after(start).count();
+        let defines_item = |token: &Token| {
+            token
+                .href
+                .and_then(|href| self.resolve(href, url_parser))
+                .is_some_and(|target| target.id == id && target.module == module_name)
+        };
+
+        let mut end = declaration_end(start);
+        while let Some((clause, head)) = end.checked_sub(1).and_then(|last| tokens.next(last)) {
+            // Clauses are not separated by blank lines, and pragmas are no clauses.
+            let follows_blank_line = tokens
+                .previous(clause)
+                .is_some_and(|(_, previous)| head.line > previous.line + 1);
+            if head.column != column
+                || follows_blank_line
+                || head.has_class("Keyword")
+                || head.is_symbol("{-#")
+            {
+                break;
+            }
+            let clause_end = declaration_end(clause);
+            let is_clause = head.is_symbol("...")
+                || (clause..clause_end)
+                    .filter_map(|index| tokens.get(index))
+                    .take_while(|token| {
+                        !(token.is_symbol("=") || token.is_symbol(":") || token.is_keyword("with"))
+                    })
+                    .any(defines_item);
+            if !is_clause {
+                break;
+            }
+            end = clause_end;
+        }
+
+        // The first line starts at its first token, so indent it like in the source.
+        let snippet: Vec<_> = (start..end).filter_map(|index| tokens.get(index)).collect();
+        let indentation = " ".repeat(column - 1);
+
+        let html: String = snippet.iter().map(|token| token.html()).collect();
+        let text: String = snippet.iter().map(|token| token.text.as_str()).collect();
+        let text = format!("{indentation}{text}");
+        let dedent = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        let text: Vec<_> = text
+            .trim_end()
+            .lines()
+            .map(|line| line.get(dedent..).unwrap_or(line.trim_start()))
+            .collect();
+
+        Some(Snippet {
+            html: format!(
+                "<pre class=\"Agda\">{indentation}{html}</pre>",
+                html = html.trim_end()
+            ),
+            text: text.join("\n"),
+        })
+    }
+
+    /// Record the definition whose `where` block declares each local definition.
+    ///
+    /// The parent is the closest item before the clause with the `where` block
+    /// that is declared at the same column the clause starts at, which is
+    /// either its type signature or the clause itself.
+    fn parse_parents(tokens: &Tokens, scopes: &Scopes, items: &mut [Item]) {
+        let positions: Vec<Option<usize>> =
+            items.iter().map(|item| tokens.position(&item.id)).collect();
+
+        for index in 0..items.len() {
+            let Some(clause) = positions[index].and_then(|position| scopes.local_clause(position))
+            else {
+                continue;
+            };
+            let column = tokens.get(clause).map(|token| token.column);
+
+            items[index].parent = positions
+                .iter()
+                .zip(items.iter())
+                .filter_map(|(position, item)| Some((position.filter(|&p| p <= clause)?, item)))
+                .filter(|&(position, _)| tokens.get(position).map(|token| token.column) == column)
+                .max_by_key(|&(position, _)| position)
+                .map(|(_, item)| item.id.clone());
+        }
+    }
+
+    /// Qualify fields by the name of their record, as in `R.field`.
+    fn qualify_fields(items: &mut [Item]) {
+        let records: HashMap<String, String> = items
+            .iter()
+            .filter(|item| item.kind == ItemKind::Record)
+            .map(|item| (item.id.clone(), item.identifier.clone()))
+            .collect();
+
+        for item in items.iter_mut().filter(|item| item.kind == ItemKind::Field) {
+            if let Some(record) = item.owner.as_ref().and_then(|id| records.get(id)) {
+                item.scope.push(record.clone());
+            }
+        }
+    }
+
+    /// Record the definitions referred to from within each item.
+    ///
+    /// References are attributed to the item whose declaration or clauses
+    /// they appear in. Bound variables and the items themselves are skipped.
+    /// A line that is not indented further than the current declaration ends it,
+    /// unless it is a clause of an item, like `zero + n = n` of `_+_`.
+    fn parse_references(
+        &self,
+        tokens: &Tokens,
+        items: &mut [Item],
+        module_name: &str,
+        url_parser: url::ParseOptions,
+    ) {
+        let by_id: HashMap<String, usize> = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (item.id.clone(), index))
+            .collect();
+        // Only functions are defined by clauses, constructors merely occur in their patterns.
+        let functions: HashMap<String, usize> = by_id
+            .iter()
+            .filter(|(_, &item)| matches!(items[item].kind, ItemKind::Function | ItemKind::Macro))
+            .map(|(id, &item)| (id.clone(), item))
+            .collect();
+        let indentations: Vec<Option<usize>> = items
+            .iter()
+            .map(|item| {
+                tokens
+                    .position(&item.id)
+                    .map(|index| tokens.indentation(index))
+            })
+            .collect();
+
+        let mut current = None;
+        for (index, token) in tokens.iter() {
+            let starts_line = !token.is_whitespace() && tokens.line_start(index) == index;
+            let ends_declaration = current.is_none_or(|item: usize| {
+                indentations[item].is_none_or(|indentation| token.column <= indentation)
+            });
+            if starts_line && ends_declaration && !token.is_symbol("...") {
+                current = self.parse_clause(tokens, index, &functions, module_name, url_parser);
+            }
+
+            if let Some(&item) = token.id.and_then(|id| by_id.get(id)) {
+                current = Some(item);
+                continue;
+            }
+
+            let Some(href) = token.href else {
+                continue;
+            };
+            if token.has_class("Bound") {
+                continue;
+            }
+            let Some(Reference { module, id }) = self.resolve(href, url_parser) else {
+                continue;
+            };
+
+            if module == module_name {
+                if token.id == Some(&id) {
+                    // Defining occurrences of anything but items, like anonymous modules
+                    continue;
+                }
+                if by_id.get(&id).is_some_and(|&item| current == Some(item)) {
+                    continue;
+                }
+            }
+
+            if let Some(current) = current {
+                let reference = Reference { module, id };
+                let references = &mut items[current].references;
+                if !references.contains(&reference) {
+                    references.push(reference);
+                }
+            }
+        }
+    }
+
+    /// The function defined by the clause starting at `index`, if the line starts a clause.
+    ///
+    /// That is the first function mentioned before the `=` or `with` of the clause.
+    /// Lines starting with a keyword, like fixity declarations, or a pragma are no clauses.
+    fn parse_clause(
+        &self,
+        tokens: &Tokens,
+        index: usize,
+        functions: &HashMap<String, usize>,
+        module_name: &str,
+        url_parser: url::ParseOptions,
+    ) -> Option<usize> {
+        let head = tokens.get(index)?;
+        if head.has_class("Keyword") || head.is_symbol("{-#") {
+            return None;
+        }
+
+        std::iter::once(head)
+            .chain(tokens.declaration_after(index))
+            .take_while(|token| {
+                !(token.is_symbol("=")
+                    || token.is_symbol(":")
+                    || token.is_keyword("with")
+                    || token.is_keyword("where"))
+            })
+            .filter(|token| !token.has_class("Bound"))
+            .filter_map(|token| self.resolve(token.href?, url_parser))
+            .find_map(|target| {
+                if target.module == module_name {
+                    functions.get(&target.id).copied()
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Fixities declared by `infix`, `infixl` and `infixr`, by the ID of the operator.
+    fn parse_fixities(
+        &self,
+        tokens: &Tokens,
+        module_name: &str,
+        url_parser: url::ParseOptions,
+    ) -> HashMap<String, Fixity> {
+        let mut fixities = HashMap::new();
+
+        for (index, token) in tokens.iter() {
+            let associativity = match token.text.as_str() {
+                "infixl" => Associativity::Left,
+                "infixr" => Associativity::Right,
+                "infix" => Associativity::NonAssociative,
+                _ => continue,
+            };
+            if !token.has_class("Keyword") {
+                continue;
+            }
+
+            // The precedence is not highlighted, but all operators are linked.
+            let mut declaration = tokens.declaration_after(index).peekable();
+            let mut precedence = String::new();
+            while let Some(token) = declaration.next_if(|token| token.href.is_none()) {
+                precedence.push_str(&token.text);
+            }
+            let fixity = Fixity {
+                associativity,
+                precedence: precedence.trim().into(),
+            };
+
+            for href in declaration.filter_map(|token| token.href) {
+                let Some(Reference { module, id }) = self.resolve(href, url_parser) else {
+                    continue;
+                };
+                if module == module_name {
+                    fixities.insert(id, fixity.clone());
+                }
+            }
+        }
+
+        fixities
+    }
+
+    /// Deprecations declared by `WARNING_ON_USAGE` pragmas, by the ID of the deprecated item.
+    fn parse_deprecations(
+        &self,
+        tokens: &Tokens,
+        module_name: &str,
+        url_parser: url::ParseOptions,
+    ) -> HashMap<String, Deprecation> {
+        let mut deprecations = HashMap::new();
+
+        for (index, _) in tokens
+            .iter()
+            .filter(|(_, token)| token.is_keyword("WARNING_ON_USAGE"))
+        {
+            // The message may start on a line of its own, so the pragma ends at `#-}`.
+            let pragma: Vec<_> = tokens
+                .iter()
+                .skip(index + 1)
+                .map(|(_, token)| token)
+                .take_while(|token| !token.is_symbol("#-}"))
+                .collect();
+
+            let Some(message) = pragma
+                .iter()
+                .find(|token| token.has_class("String"))
+                .map(|token| string_literal(&token.text))
+            else {
+                continue;
+            };
+            let deprecation = Deprecation {
+                replacement: suggested_replacement(&message),
+                message,
+            };
+
+            for href in pragma.iter().filter_map(|token| token.href) {
+                let Some(Reference { module, id }) = self.resolve(href, url_parser) else {
+                    continue;
+                };
+                if module == module_name {
+                    deprecations.insert(id, deprecation.clone());
+                }
+            }
+        }
+
+        deprecations
+    }
+
+    /// Collect the definitions bound to builtins by `BUILTIN` pragmas.
+    fn parse_builtins(&self, tokens: &Tokens, url_parser: url::ParseOptions) -> Vec<Builtin> {
+        tokens
+            .iter()
+            .filter(|(_, token)| token.is_keyword("BUILTIN"))
+            .filter_map(|(index, _)| {
+                let mut pragma = tokens
+                    .iter()
+                    .skip(index + 1)
+                    .map(|(_, token)| token)
+                    .take_while(|token| !token.is_symbol("#-}"))
+                    .filter(|token| !token.is_whitespace());
+
+                let name = pragma.next()?.text.trim().to_string();
+                let definition = pragma.next()?;
+                let target = self.resolve(definition.href?, url_parser)?;
+
+                Some(Builtin {
+                    name,
+                    definition: format!("{}.{}", target.module, definition.text),
+                    target,
+                })
+            })
+            .collect()
+    }
+
+    /// Collect the pragmas that turn off some of Agda's checks, like `TERMINATING`.
+    ///
+    /// Each pragma is attributed to the definition it names or, if it names none,
+    /// to the definition that follows it.
+    fn parse_trust_pragmas(
+        &self,
+        tokens: &Tokens,
+        url_parser: url::ParseOptions,
+    ) -> Vec<TrustPragma> {
+        tokens
+            .iter()
+            .filter(|(_, token)| {
+                token.has_class("Keyword") && TRUST_PRAGMAS.contains(&token.text.as_str())
+            })
+            .filter_map(|(index, token)| {
+                let definition = tokens
+                    .iter()
+                    .skip(index + 1)
+                    .map(|(_, token)| token)
+                    .find(|token| token.href.is_some())
+                    .and_then(|definition| {
+                        let target = self.resolve(definition.href?, url_parser)?;
+                        Some(format!("{}.{}", target.module, definition.text))
+                    });
+
+                Some(TrustPragma {
+                    pragma: token.text.clone(),
+                    id: token.id?.into(),
+                    position: Position {
+                        line: token.line,
+                        column: token.column,
+                    },
+                    definition,
+                })
+            })
+            .collect()
+    }
+
+    /// Collect the problems Agda highlighted, like unsolved metas.
+    ///
+    /// Adjacent tokens highlighted for the same problem are reported together.
+    fn parse_diagnostics(tokens: &Tokens) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<Diagnostic> = Vec::new();
+        // Class of the previous non-whitespace token, if it was highlighted as a problem.
+        let mut previous = None;
+
+        for (_, token) in tokens.iter() {
+            if token.is_whitespace() {
+                continue;
+            }
+            let class = PROBLEM_CLASSES
+                .iter()
+                .copied()
+                .find(|class| token.has_class(class));
+
+            match (class, diagnostics.last_mut()) {
+                (None, _) => {}
+                (Some(class), Some(diagnostic)) if previous == Some(class) => {
+                    diagnostic.text.push(' ');
+                    diagnostic.text.push_str(&token.text);
+                }
+                (Some(class), _) => diagnostics.push(Diagnostic {
+                    class: class.into(),
+                    id: token.id.map(String::from),
+                    position: Position {
+                        line: token.line,
+                        column: token.column,
+                    },
+                    text: token.text.clone(),
+                }),
+            }
+            previous = class;
+        }
+
+        diagnostics
+    }
+
+    /// Name of the top-level module declared in a rendered module.
+    fn parse_module_name(tokens: &Tokens) -> Option<String> {
+        let name = Self::module_declaration(tokens)?;
+        tokens.get(name).map(|token| token.text.clone())
+    }
+
+    /// Index of the name in the declaration of the top-level module.
+    fn module_declaration(tokens: &Tokens) -> Option<usize> {
+        tokens
+            .iter()
+            .filter(|(_, token)| token.is_keyword("module"))
+            .filter_map(|(index, _)| tokens.next(index))
+            .find(|(_, token)| {
+                token.has_class("Module") && token.href.is_some_and(|href| !href.contains('#'))
+            })
+            .map(|(index, _)| index)
+    }
+
+    /// Parameters of the top-level module, like `{a} {A : Set a}` in
+    ///
+    ///     module Data.List.Properties {a} {A : Set a} where
+    fn parse_telescope(tokens: &Tokens) -> Option<Telescope> {
+        let name = Self::module_declaration(tokens)?;
+
+        let mut text = String::new();
+        let mut binders = Vec::new();
+        let mut binder = Vec::new();
+        let mut hiding = Hiding::Explicit;
+        let mut depth = 0;
+
+        for token in tokens.declaration_after(name) {
+            if token.has_class("Comment") {
+                continue;
+            }
+            if depth == 0 && token.is_keyword("where") {
+                break;
+            }
+
+            let nesting = token.nesting();
+            depth += nesting;
+            if depth < 0 {
+                break;
+            }
+            text.push_str(&token.text);
+
+            match (depth, nesting) {
+                (1, 1) => {
+                    hiding = match token.text.as_str() {
+                        "{" => Hiding::Implicit,
+                        "{{" | "⦃" => Hiding::Instance,
+                        _ => Hiding::Explicit,
+                    };
+                    binder.clear();
+                }
+                (0, -1) => binders.push(Binder::new(hiding, &binder)),
+                (0, _) if !token.is_whitespace() => {
+                    binders.push(Binder::new(Hiding::Explicit, &[token]));
+                }
+                (0, _) => {}
+                _ => binder.push(token),
+            }
+        }
+
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        (!text.is_empty()).then_some(Telescope { text, binders })
+    }
+
+    /// Collect the flags set by `{-# OPTIONS … #-}` pragmas.
+    fn parse_options(tokens: &Tokens) -> Vec<String> {
+        let mut options: Vec<String> = Vec::new();
+
+        for (index, _) in tokens
+            .iter()
+            .filter(|(_, token)| token.is_keyword("OPTIONS"))
+        {
+            let flags = tokens
+                .iter()
+                .skip(index + 1)
+                .map(|(_, token)| token)
+                .take_while(|token| !token.is_symbol("#-}"))
+                .flat_map(|token| token.text.split_whitespace());
+            for flag in flags {
+                if !options.iter().any(|option| option == flag) {
+                    options.push(flag.into());
+                }
+            }
+        }
+
+        options
+    }
+
+    /// Collect the names of all modules imported by `import M`.
+    fn parse_imports(tokens: &Tokens) -> Vec<String> {
+        let mut imports: Vec<String> = Vec::new();
+
+        let imported = tokens
+            .iter()
+            .filter(|(_, token)| token.is_keyword("import"))
+            .filter_map(|(index, _)| tokens.next(index))
+            .filter(|(_, token)| token.has_class("Module"));
+
+        for (_, token) in imported {
+            let name = token
+                .href
+                .and_then(|href| Path::new(href).file_stem())
+                .and_then(|stem| stem.to_str())
+                .unwrap_or(&token.text);
+            if !imports.iter().any(|import| import == name) {
+                imports.push(name.into());
+            }
+        }
+
+        imports
+    }
+
+    /// Parse a rendered module, either an HTML page or a Markdown file with `is_markdown`.
+    pub fn parse_module(&self, content: &str, is_markdown: bool) -> Result<Module> {
+        let url_parser = Url::options().base_url(Some(&self.base_url));
+        let document = Html::parse_document(content);
+
+        let tokens = Tokens::new(&document, &self.code);
+
+        // Modules rendered to Markdown have no title, but still declare their name.
+        let name = document
+            .select(&self.title)
+            .next()
+            .map(|el| el.inner_html())
+            .or_else(|| Self::parse_module_name(&tokens))
+            .context("No module name")?;
+        let scopes = Scopes::new(&tokens);
+
+        let mut items: Vec<Item> = document
+            .select(&self.items)
+            .filter_map(
+                |item| match self.parse_item(item, &tokens, &scopes, &name, url_parser) {
+                    Ok(item) => item,
+                    Err(err) => {
+                        eprintln!("Warning: Skipping item ({})", err);
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        Self::parse_parents(&tokens, &scopes, &mut items);
+        Self::qualify_fields(&mut items);
+        let mut fixities = self.parse_fixities(&tokens, &name, url_parser);
+        let mut deprecations = self.parse_deprecations(&tokens, &name, url_parser);
+        for item in &mut items {
+            item.fixity = fixities.remove(&item.id);
+            item.deprecation = deprecations.remove(&item.id);
+        }
+        self.parse_references(&tokens, &mut items, &name, url_parser);
+        let summary = Self::parse_summary(&tokens).or_else(|| {
+            // Prose of modules rendered to Markdown is not part of any code block.
+            if is_markdown {
+                self.parse_prose(&document)
+            } else {
+                None
+            }
+        });
+        let imports = Self::parse_imports(&tokens);
+        let telescope = Self::parse_telescope(&tokens);
+        let options = Self::parse_options(&tokens);
+        let builtins = self.parse_builtins(&tokens, url_parser);
+        let trust_pragmas = self.parse_trust_pragmas(&tokens, url_parser);
+        let diagnostics = Self::parse_diagnostics(&tokens);
+
+        Ok(Module {
+            name,
+            summary,
+            telescope,
+            options,
+            builtins,
+            trust_pragmas,
+            diagnostics,
+            imports,
+            items,
+        })
+    }
+}
+
+/// Contents of an Agda string literal, without quotes and escapes.
+fn string_literal(literal: &str) -> String {
+    let contents = literal.trim();
+    let contents = contents.strip_prefix('"').unwrap_or(contents);
+    let contents = contents.strip_suffix('"').unwrap_or(contents);
+    contents
+        .replace("\\n", "\n")
+        .replace("\\\"", "\"")
+        .replace("\\\\", "\\")
+}
+
+/// The name in a deprecation message like `Please use X instead.`
+fn suggested_replacement(message: &str) -> Option<String> {
+    let (_, suggestion) = message
+        .split_once("Please use ")
+        .or_else(|| message.split_once("please use "))?;
+    let (replacement, _) = suggestion.split_once(" instead")?;
+    Some(replacement.trim().into()).filter(|replacement: &String| !replacement.is_empty())
+}
+
+/// Text of a block of comments, without comment markers.
+fn comment_text<'c>(comments: impl Iterator<Item = &'c str>) -> Option<String> {
+    let text = comments
+        .flat_map(|comment| {
+            let text = match comment.strip_prefix("{-") {
+                Some(block) => block.strip_suffix("-}").unwrap_or(block),
+                None => comment,
+            };
+            text.lines()
+        })
+        .map(|line| line.trim().trim_start_matches('-').trim())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+/// Strip a YAML front matter block `---` … `---` from the start of a Markdown document.
+fn strip_front_matter(markdown: &str) -> &str {
+    markdown
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n"))
+        .map_or(markdown, |(_, rest)| rest)
+}
+
+/// The first paragraph of literate prose that is not a heading, on a single line.
+fn first_paragraph(prose: &str) -> Option<String> {
+    prose
+        .split("\n\n")
+        .map(|paragraph| paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+        .find(|paragraph| !paragraph.is_empty() && !paragraph.starts_with('#'))
+}
+
+/// What sort of declaration an item is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ItemKind {
+    Module,
+    Function,
+    Datatype,
+    Constructor,
+    Record,
+    Field,
+    Postulate,
+    Primitive,
+    Macro,
+    PatternSynonym,
+    Generalizable,
+}
+
+impl ItemKind {
+    /// Determine the kind of an item from its Agda highlighting classes.
+    fn from_classes(classes: &[String]) -> Option<Self> {
+        classes.iter().find_map(|class| match class.as_str() {
+            "Module" => Some(Self::Module),
+            "Function" => Some(Self::Function),
+            "Datatype" => Some(Self::Datatype),
+            "InductiveConstructor" | "CoinductiveConstructor" => Some(Self::Constructor),
+            "Record" => Some(Self::Record),
+            "Field" => Some(Self::Field),
+            "Postulate" => Some(Self::Postulate),
+            "Primitive" | "PrimitiveType" => Some(Self::Primitive),
+            "Macro" => Some(Self::Macro),
+            "Generalizable" => Some(Self::Generalizable),
+            _ => None,
+        })
+    }
+}
+
+impl FromStr for ItemKind {
+    type Err = &'static str;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        match kind {
+            "module" => Ok(Self::Module),
+            "function" => Ok(Self::Function),
+            "datatype" => Ok(Self::Datatype),
+            "constructor" => Ok(Self::Constructor),
+            "record" => Ok(Self::Record),
+            "field" => Ok(Self::Field),
+            "postulate" => Ok(Self::Postulate),
+            "primitive" => Ok(Self::Primitive),
+            "macro" => Ok(Self::Macro),
+            "pattern-synonym" => Ok(Self::PatternSynonym),
+            "generalizable" => Ok(Self::Generalizable),
+            _ => Err(
+                "expected one of 'module', 'function', 'datatype', 'constructor', 'record', 'field', \
+                'postulate', 'primitive', 'macro', 'pattern-synonym' or 'generalizable'",
+            ),
+        }
+    }
+}
+
+/// Whether an item can be used from outside its module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Visibility {
+    Public,
+    /// Declared in a `private` block.
+    Private,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Item {
+    pub id: String,
+    pub identifier: String,
+    /// Additional names to search the item by.
+    pub aliases: Vec<String>,
+    /// Names of the nested modules the item is declared in, outermost first,
+    /// followed by the record for fields.
+    pub scope: Vec<String>,
+    pub kind: ItemKind,
+    pub visibility: Visibility,
+    /// Whether the item is declared in an `abstract` block.
+    pub is_abstract: bool,
+    /// Whether the item is declared in an `instance` block.
+    pub is_instance: bool,
+    /// The record type the instance provides, from the head of its type signature.
+    pub instance_of: Option<Reference>,
+    /// Position of the defining occurrence in the source file.
+    pub position: Option<Position>,
+    pub classes: Vec<String>,
+    /// Rendered type signature of the definition, as plain text.
+    pub signature: Option<String>,
+    /// Comments preceding the definition, without comment markers.
+    pub doc: Option<String>,
+    /// The definition this item renames, if it is introduced by `renaming (X to Y)`.
+    pub alias_of: Option<AliasTarget>,
+    /// ID of the definition whose `where` block declares this item, if it is local.
+    pub parent: Option<String>,
+    /// ID of the data type or record declaring this constructor or field.
+    pub owner: Option<String>,
+    /// Associativity and precedence of the operator, if it has a fixity declaration.
+    pub fixity: Option<Fixity>,
+    /// Warning shown on uses of the item, if it is deprecated by `WARNING_ON_USAGE`.
+    pub deprecation: Option<Deprecation>,
+    /// Source of the declaration, if the parser was asked to extract it.
+    pub snippet: Option<Snippet>,
+    /// Definitions this item refers to, in order of first occurrence.
+    pub references: Vec<Reference>,
+}
+
+impl Item {
+    /// Fully qualified name of the item, declared in the module `module_name`.
+    pub fn qualified_name(&self, module_name: &str) -> String {
+        self.qualify(module_name, &self.identifier)
+    }
+
+    /// Fully qualified name of the item, with Unicode symbols spelled out as
+    /// Agda input method sequences. `None` if there is nothing to spell out.
+    pub fn input_method_name(&self, module_name: &str) -> Option<String> {
+        names::transliterate(&self.qualified_name(module_name))
+    }
+
+    /// Qualify `name` like the item, e.g. to qualify one of its aliases.
+    pub fn qualify(&self, module_name: &str, name: &str) -> String {
+        let mut qualified = module_name.to_string();
+        for part in self.scope.iter().map(String::as_str).chain([name]) {
+            qualified.push('.');
+            qualified.push_str(part);
+        }
+        qualified
+    }
+}
+
+impl Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{identifier}#{id}",
+            identifier = self.identifier,
+            id = self.id
+        )
+    }
+}
+
+/// Line and column of a token in its source file, both starting at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{line}:{column}", line = self.line, column = self.column)
+    }
+}
+
+/// How an operator associates, according to its fixity declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Associativity {
+    /// Declared by `infixl`.
+    Left,
+    /// Declared by `infixr`.
+    Right,
+    /// Declared by `infix`.
+    NonAssociative,
+}
+
+/// Associativity and precedence of an operator, like `infixl 6`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Fixity {
+    pub associativity: Associativity,
+    /// Precedence as written in the declaration, since it need not be an integer.
+    pub precedence: String,
+}
+
+impl Display for Fixity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let keyword = match self.associativity {
+            Associativity::Left => "infixl",
+            Associativity::Right => "infixr",
+            Associativity::NonAssociative => "infix",
+        };
+        write!(f, "{keyword} {precedence}", precedence = self.precedence)
+    }
+}
+
+/// The warning Agda shows when a deprecated definition is used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Deprecation {
+    pub message: String,
+    /// Name of the definition to use instead, if the message suggests one.
+    pub replacement: Option<String>,
+}
+
+/// A definition bound to a builtin, like `ℕ` in `{-# BUILTIN NATURAL ℕ #-}`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Builtin {
+    /// Name of the builtin, like `NATURAL`.
+    pub name: String,
+    /// Name of the definition, qualified by the module it is defined in.
+    pub definition: String,
+    pub target: Reference,
+}
+
+/// Pragmas that make Agda trust a definition instead of checking it.
+const TRUST_PRAGMAS: &[&str] = &[
+    "TERMINATING",
+    "NON_TERMINATING",
+    "NO_POSITIVITY_CHECK",
+    "NO_UNIVERSE_CHECK",
+    "INJECTIVE",
+];
+
+/// A pragma that turns off some of Agda's checks, like `{-# TERMINATING #-}`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrustPragma {
+    /// Name of the pragma, like `TERMINATING`.
+    pub pragma: String,
+    /// ID of the anchor of the pragma's name.
+    pub id: String,
+    pub position: Position,
+    /// Name of the definition the pragma applies to, qualified by the module it is defined in.
+    pub definition: Option<String>,
+}
+
+/// Classes Agda highlights problems with, which only occur in modules that do not check.
+const PROBLEM_CLASSES: &[&str] = &[
+    "UnsolvedMeta",
+    "UnsolvedConstraint",
+    "TerminationProblem",
+    "PositivityProblem",
+    "CoverageProblem",
+    "Deadcode",
+    "CatchallClause",
+    "MissingDefinition",
+];
+
+/// A problem Agda highlighted, like `TerminationProblem`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    /// Class the problem is highlighted with.
+    pub class: String,
+    /// ID of the anchor of the first highlighted token.
+    pub id: Option<String>,
+    pub position: Position,
+    /// The highlighted source text.
+    pub text: String,
+}
+
+/// Source of a declaration, from its type signature through its clauses.
+#[derive(Debug, Clone, Serialize)]
+pub struct Snippet {
+    /// The source as rendered by Agda, in a `<pre class="Agda">`.
+    pub html: String,
+    /// The source as plain text, without common indentation.
+    pub text: String,
+}
+
+/// A link from one definition to the site of another.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Reference {
+    pub module: String,
+    pub id: String,
+}
+
+impl Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{module}.html#{id}", module = self.module, id = self.id)
+    }
+}
+
+/// The definition renamed by an alias.
+#[derive(Debug, Clone, Serialize)]
+pub struct AliasTarget {
+    /// Name of the definition, qualified by the module it is defined in.
+    pub name: String,
+    pub target: Reference,
+}
+
+/// Parameters of a module.
+#[derive(Debug, Clone, Serialize)]
+pub struct Telescope {
+    /// The parameters as written, with whitespace normalized.
+    pub text: String,
+    pub binders: Vec<Binder>,
+}
+
+/// How a parameter is passed, depending on the brackets around its binder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Hiding {
+    /// `(x : A)`, or just `x`.
+    Explicit,
+    /// `{x : A}`.
+    Implicit,
+    /// `⦃ x : A ⦄` or `{{ x : A }}`.
+    Instance,
+}
+
+/// Names bound by a single binder of a telescope, like `{A B : Set a}`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Binder {
+    pub names: Vec<String>,
+    /// Type of the bound names, unless it is left for Agda to infer.
+    #[serde(rename = "type")]
+    pub ty: Option<String>,
+    pub hiding: Hiding,
+}
+
+impl Binder {
+    /// Split the tokens between the brackets of a binder at the first `:` not nested in brackets.
+    fn new(hiding: Hiding, tokens: &[&Token]) -> Self {
+        let mut depth = 0;
+        let colon = tokens.iter().position(|token| {
+            depth += token.nesting();
+            depth == 0 && token.is_symbol(":")
+        });
+
+        let text = |tokens: &[&Token]| {
+            tokens
+                .iter()
+                .map(|token| token.text.as_str())
+                .collect::<String>()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let (names, ty) = match colon {
+            Some(colon) => (text(&tokens[..colon]), Some(text(&tokens[colon + 1..]))),
+            None => (text(tokens), None),
+        };
+
+        Self {
+            names: names.split_whitespace().map(String::from).collect(),
+            ty,
+            hiding,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Module {
+    pub name: String,
+    /// Leading comment or prose of the module.
+    pub summary: Option<String>,
+    /// Parameters of the module, if it has any.
+    pub telescope: Option<Telescope>,
+    /// Flags set by `OPTIONS` pragmas, in order.
+    pub options: Vec<String>,
+    /// Definitions bound to builtins by `BUILTIN` pragmas in this module.
+    pub builtins: Vec<Builtin>,
+    /// Pragmas turning off checks of Agda, in order.
+    pub trust_pragmas: Vec<TrustPragma>,
+    /// Problems Agda highlighted when rendering the module, in order.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Names of the modules imported by this module.
+    pub imports: Vec<String>,
+    pub items: Vec<Item>,
+}
+
+/// Options enabled by other options, like `--without-K` by `--cubical-compatible`.
+const IMPLIED_OPTIONS: &[(&str, &str)] = &[
+    ("--cubical", "--cubical-compatible"),
+    ("--erased-cubical", "--cubical-compatible"),
+    ("--cubical-compatible", "--without-K"),
+];
+
+/// Options that modules importing a module using them have to use as well.
+const INFECTIVE_OPTIONS: &[&str] = &[
+    "--allow-exec",
+    "--cohesion",
+    "--cubical",
+    "--erased-cubical",
+    "--flat-split",
+    "--guarded",
+    "--rewriting",
+    "--two-level",
+];
+
+impl Module {
+    /// The infective options the module's `OPTIONS` pragmas enable, like `--cubical`.
+    pub fn infective_options(&self) -> impl Iterator<Item = &str> {
+        self.options
+            .iter()
+            .map(String::as_str)
+            .filter(|option| INFECTIVE_OPTIONS.contains(option))
+    }
+
+    /// Whether the module's `OPTIONS` pragmas enable `flag`, directly or through another option.
+    ///
+    /// Options set for the whole library in its `.agda-lib` file are not taken into account.
+    pub fn has_option(&self, flag: &str) -> bool {
+        self.options.iter().any(|option| option == flag)
+            || IMPLIED_OPTIONS
+                .iter()
+                .any(|&(option, implied)| implied == flag && self.has_option(option))
+    }
+}
+
+impl Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const NAMES: &[(&str, &str)] = &[
+        ("ℕ", "Datatype"),
+        ("zero", "InductiveConstructor"),
+        ("suc", "InductiveConstructor"),
+        ("_+_", "Function Operator"),
+        ("double", "Function"),
+        ("twice", "Function"),
+        ("go", "Function"),
+        ("helper", "Function"),
+        ("old", "Function"),
+        ("after", "Function"),
+        ("n", "Bound"),
+        ("m", "Bound"),
+        ("Data.Nat", "Module @Data.Nat.html"),
+        ("_≤_", "Datatype @Data.Nat.html#1200"),
+        ("_<_", "Function @Data.Nat.html#1300"),
+        ("Pair", "Record"),
+        ("fst", "Field"),
+        ("snd", "Field"),
+        ("_≤ℕ_", "Datatype @"),
+        ("_<ℕ_", "Function @"),
+    ];
+
+    fn parse(source: &str, snippets: bool) -> Module {
+        let html = fixtures::render("M", source, NAMES);
+        ModuleParser::new()
+            .with_snippets(snippets)
+            .parse_module(&html, false)
+            .unwrap()
+    }
+
+    fn item<'m>(module: &'m Module, identifier: &str) -> &'m Item {
+        module
+            .items
+            .iter()
+            .find(|item| item.identifier == identifier)
+            .unwrap_or_else(|| panic!("No item {identifier}"))
+    }
+
+    /// Identifiers of the items of `module` referenced by the item `identifier`.
+    fn references<'m>(module: &'m Module, identifier: &str) -> Vec<&'m str> {
+        item(module, identifier)
+            .references
+            .iter()
+            .filter_map(|reference| module.items.iter().find(|item| item.id == reference.id))
+            .map(|item| item.identifier.as_str())
+            .collect()
+    }
+
+    fn snippet(module: &Module, identifier: &str) -> String {
+        item(module, identifier)
+            .snippet
+            .as_ref()
+            .unwrap()
+            .text
+            .clone()
+    }
+
+    #[test]
+    fn snippets_span_signature_and_clauses() {
+        let module = parse(
+            indoc! {"
+                module M where
+                data ℕ : Set where
+                  zero : ℕ
+                  suc : ℕ → ℕ
+                _+_ : ℕ → ℕ → ℕ
+                zero + m = m
+                suc n + m = suc (n + m)
+                double : ℕ → ℕ
+                double n = n + n
+            "},
+            true,
+        );
+
+        assert_eq!(
+            snippet(&module, "_+_"),
+            "_+_ : ℕ → ℕ → ℕ\nzero + m = m\nsuc n + m = suc (n + m)"
+        );
+        assert_eq!(
+            snippet(&module, "double"),
+            "double : ℕ → ℕ\ndouble n = n + n"
+        );
+    }
+
+    #[test]
+    fn snippets_end_at_pragmas_and_blank_lines() {
+        let module = parse(
+            indoc! {r#"
+                module M where
+                data ℕ : Set where
+                  zero : ℕ
+                  suc : ℕ → ℕ
+                {-# BUILTIN NATURAL ℕ #-}
+                old : ℕ
+                old = zero
+                {-# WARNING_ON_USAGE old "Use zero" #-}
+
+                after : ℕ
+                after = zero
+
+                after = zero
+            "#},
+            true,
+        );
+
+        assert_eq!(
+            snippet(&module, "ℕ"),
+            "data ℕ : Set where\n  zero : ℕ\n  suc : ℕ → ℕ"
+        );
+        assert_eq!(snippet(&module, "old"), "old : ℕ\nold = zero");
+        assert_eq!(snippet(&module, "after"), "after : ℕ\nafter = zero");
+    }
+
+    #[test]
+    fn references_are_attributed_to_clauses() {
+        let module = parse(
+            indoc! {"
+                module M where
+                data ℕ : Set where
+                  zero : ℕ
+                  suc : ℕ → ℕ
+                {-# BUILTIN NATURAL ℕ #-}
+                _+_ : ℕ → ℕ → ℕ
+                zero + m = m
+                suc n + m = suc (n + m)
+                twice : ℕ → ℕ
+                twice n = n
+                infixl 6 _+_
+                double : ℕ → ℕ
+                double n = n + n
+            "},
+            false,
+        );
+
+        assert_eq!(references(&module, "_+_"), ["ℕ", "zero", "suc"]);
+        assert_eq!(references(&module, "zero"), ["ℕ"]);
+        assert_eq!(references(&module, "suc"), ["ℕ"]);
+        assert_eq!(references(&module, "twice"), ["ℕ"]);
+        assert_eq!(references(&module, "double"), ["ℕ", "_+_"]);
+    }
+
+    #[test]
+    fn where_blocks_declare_local_definitions() {
+        let module = parse(
+            indoc! {"
+                module M where
+                data ℕ : Set where
+                  zero : ℕ
+                double : ℕ → ℕ
+                double n = go n
+                  where
+                  go : ℕ → ℕ
+                  go m = m
+                twice : ℕ → ℕ
+                twice n = helper n where
+                  helper : ℕ → ℕ
+                  helper m = double (double m)
+            "},
+            false,
+        );
+
+        let double = item(&module, "double");
+        let twice = item(&module, "twice");
+        assert_eq!(item(&module, "go").parent.as_ref(), Some(&double.id));
+        assert_eq!(item(&module, "helper").parent.as_ref(), Some(&twice.id));
+        assert_eq!(double.parent, None);
+        assert_eq!(twice.parent, None);
+        assert_eq!(references(&module, "double"), ["ℕ", "go"]);
+        assert_eq!(references(&module, "helper"), ["ℕ", "double"]);
+    }
+
+    #[test]
+    fn aliases_resolve_to_exported_renamings() {
+        let module = parse(
+            indoc! {"
+                module M where
+                open import Data.Nat renaming (_≤_ to _≤ℕ_) public
+                open import Data.Nat
+                  renaming (_<_ to _<ℕ_)
+            "},
+            false,
+        );
+
+        let alias = item(&module, "_≤ℕ_").alias_of.as_ref().unwrap();
+        assert_eq!(alias.name, "Data.Nat._≤_");
+        assert_eq!(alias.target.to_string(), "Data.Nat.html#1200");
+        assert!(module.items.iter().all(|item| item.identifier != "_<ℕ_"));
+    }
+
+    #[test]
+    fn fields_are_qualified_by_their_record() {
+        let module = parse(
+            indoc! {"
+                module M where
+                record Pair : Set where
+                  field
+                    fst snd : Set
+            "},
+            false,
+        );
+
+        assert_eq!(item(&module, "fst").qualified_name("M"), "M.Pair.fst");
+        assert_eq!(item(&module, "snd").qualified_name("M"), "M.Pair.snd");
+        assert_eq!(item(&module, "Pair").qualified_name("M"), "M.Pair");
+    }
+}
@@ -0,0 +1,72 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::{collections::HashMap, sync::OnceLock};
+
+/// Search aliases of a mixfix operator like `if_then_else_`.
+///
+/// These are the name parts without underscores (`if then else`) and the
+/// display form with holes set apart (`if _ then _ else _`). Names without
+/// holes have no aliases.
+pub fn mixfix_aliases(identifier: &str) -> Vec<String> {
+    let parts: Vec<&str> = identifier
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .collect();
+    if !identifier.contains('_') || parts.is_empty() {
+        return Vec::new();
+    }
+
+    let display = identifier
+        .split('_')
+        .collect::<Vec<_>>()
+        .join(" _ ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    vec![parts.join(" "), display]
+}
+
+/// Unicode symbols and the Agda input method sequences typing them.
+const INPUT_METHOD_TABLE: &str = include_str!("../../resources/agda-input.tsv");
+
+fn input_method() -> &'static HashMap<char, &'static str> {
+    static INPUT_METHOD: OnceLock<HashMap<char, &'static str>> = OnceLock::new();
+
+    INPUT_METHOD.get_or_init(|| {
+        INPUT_METHOD_TABLE
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let (symbol, sequence) = line.split_once('\t')?;
+                let mut symbol = symbol.chars();
+                match (symbol.next(), symbol.next()) {
+                    (Some(symbol), None) => Some((symbol, sequence)),
+                    _ => None,
+                }
+            })
+            .collect()
+    })
+}
+
+/// Spell out the Unicode symbols in `name` as the input method sequences typing them,
+/// e.g. `_≤_` as `_\le_`.
+///
+/// Returns `None` if `name` contains no symbols with a known sequence.
+pub fn transliterate(name: &str) -> Option<String> {
+    let table = input_method();
+    if !name.chars().any(|c| table.contains_key(&c)) {
+        return None;
+    }
+
+    let mut transliterated = String::with_capacity(name.len());
+    for c in name.chars() {
+        match table.get(&c) {
+            Some(sequence) => transliterated.push_str(sequence),
+            None => transliterated.push(c),
+        }
+    }
+    Some(transliterated)
+}
@@ -0,0 +1,277 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ops::Range;
+
+use super::tokens::Tokens;
+
+/// Body of a module declared by `module M … where` inside a rendered module.
+#[derive(Debug)]
+struct Region {
+    /// Indices of the tokens making up the body.
+    body: Range<usize>,
+    /// Name of the module, or `None` for anonymous modules `module _ … where`.
+    name: Option<String>,
+}
+
+/// Local definitions in a `where` block attached to a clause.
+#[derive(Debug)]
+struct Locals {
+    /// Indices of the tokens making up the block.
+    body: Range<usize>,
+    /// Index of the first token of the clause the block belongs to.
+    clause: usize,
+}
+
+/// A `data` or `record` declaration, which declares constructors or fields.
+#[derive(Debug)]
+struct Declaration {
+    /// Indices of the tokens after the name of the declared type.
+    body: Range<usize>,
+    /// Index of the name of the declared type.
+    name: usize,
+}
+
+/// Nested modules, `data` and `record` declarations and other blocks of a rendered module.
+#[derive(Debug)]
+pub struct Scopes {
+    regions: Vec<Region>,
+    declarations: Vec<Declaration>,
+    locals: Vec<Locals>,
+    private: Vec<Range<usize>>,
+    abstract_: Vec<Range<usize>>,
+    instance: Vec<Range<usize>>,
+}
+
+/// Indices of the tokens in blocks opened by `keyword`, like `private`.
+fn keyword_blocks(tokens: &Tokens, keyword: &str) -> Vec<Range<usize>> {
+    tokens
+        .iter()
+        .filter(|(_, token)| token.is_keyword(keyword))
+        .map(|(index, _)| {
+            let start = index + 1;
+            start..start + tokens.declaration_after(index).count()
+        })
+        .collect()
+}
+
+/// Index of the first token of the declaration a `where` at `index` belongs to.
+///
+/// That is the closest token before it that starts a line and is indented less.
+fn declaration_start(tokens: &Tokens, index: usize) -> Option<usize> {
+    let column = tokens.get(index)?.column;
+    tokens
+        .before(index)
+        .filter(|(_, token)| !token.is_whitespace() && token.column < column)
+        .map(|(start, _)| start)
+        .find(|&start| tokens.line_start(start) == start)
+}
+
+impl Scopes {
+    pub fn new(tokens: &Tokens) -> Self {
+        let regions = tokens
+            .iter()
+            .filter(|(_, token)| token.is_keyword("module"))
+            .filter_map(|(index, _)| {
+                let (_, name) = tokens.next(index)?;
+                if name.href.is_some_and(|href| !href.contains('#')) {
+                    // The module a file is named after has no fragment in its link.
+                    // Its contents are qualified by the module name of the file already.
+                    return None;
+                }
+
+                // Everything after `where` that is indented further than the
+                // `module` keyword belongs to the module. Declarations like
+                // `module M = N` do not have a body.
+                let mut depth = 0;
+                let mut declaration = tokens.declaration_after(index).enumerate();
+                let (offset, _) = declaration.find(|(_, token)| {
+                    depth += token.nesting();
+                    depth == 0 && token.is_keyword("where")
+                })?;
+
+                let start = index + 1 + offset + 1;
+                let body = start..start + declaration.count();
+
+                let name = (name.text != "_").then(|| name.text.clone());
+                Some(Region { body, name })
+            })
+            .collect();
+
+        let declarations = tokens
+            .iter()
+            .filter(|(_, token)| token.is_keyword("data") || token.is_keyword("record"))
+            .filter_map(|(index, _)| {
+                let (name, _) = tokens.next(index)?;
+                let end = index + 1 + tokens.declaration_after(index).count();
+                Some(Declaration {
+                    body: name + 1..end,
+                    name,
+                })
+            })
+            .collect();
+
+        let locals = tokens
+            .iter()
+            .filter(|(_, token)| token.is_keyword("where"))
+            .filter_map(|(index, _)| {
+                // Modules, records and data types are declared with a keyword.
+                // Clauses of functions start with the name of the function or a pattern.
+                let clause = declaration_start(tokens, index)?;
+                if tokens.get(clause)?.has_class("Keyword") {
+                    return None;
+                }
+
+                // The block may start on a line of its own, with the local
+                // definitions at the same column as `where`. It ends at the
+                // first line not indented further than the clause.
+                let where_line = tokens.get(index)?.line;
+                let column = tokens.get(clause)?.column;
+                let start = index + 1;
+                let length = tokens
+                    .iter()
+                    .skip(start)
+                    .take_while(|(_, token)| {
+                        token.is_whitespace() || token.line == where_line || token.column > column
+                    })
+                    .count();
+                Some(Locals {
+                    body: start..start + length,
+                    clause,
+                })
+            })
+            .collect();
+
+        Self {
+            regions,
+            declarations,
+            locals,
+            private: keyword_blocks(tokens, "private"),
+            abstract_: keyword_blocks(tokens, "abstract"),
+            instance: keyword_blocks(tokens, "instance"),
+        }
+    }
+
+    /// Whether the token at `index` is declared in a `private` block.
+    pub fn is_private(&self, index: usize) -> bool {
+        self.private.iter().any(|block| block.contains(&index))
+    }
+
+    /// Whether the token at `index` is declared in an `abstract` block.
+    pub fn is_abstract(&self, index: usize) -> bool {
+        self.abstract_.iter().any(|block| block.contains(&index))
+    }
+
+    /// Whether the token at `index` is declared in an `instance` block.
+    pub fn is_instance(&self, index: usize) -> bool {
+        self.instance.iter().any(|block| block.contains(&index))
+    }
+
+    /// Index of the name of the innermost `data` or `record` declaration containing `index`.
+    pub fn declaring(&self, index: usize) -> Option<usize> {
+        self.declarations
+            .iter()
+            .filter(|declaration| declaration.body.contains(&index))
+            .max_by_key(|declaration| declaration.body.start)
+            .map(|declaration| declaration.name)
+    }
+
+    /// Index of the first token of the clause whose innermost `where` block contains `index`.
+    pub fn local_clause(&self, index: usize) -> Option<usize> {
+        self.locals
+            .iter()
+            .filter(|locals| locals.body.contains(&index))
+            .max_by_key(|locals| locals.body.start)
+            .map(|locals| locals.clause)
+    }
+
+    /// Names of the named modules enclosing the token at `index`, outermost first.
+    pub fn enclosing(&self, index: usize) -> Vec<String> {
+        self.regions
+            .iter()
+            .filter(|region| region.body.contains(&index))
+            .filter_map(|region| region.name.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use scraper::{Html, Selector};
+
+    use super::*;
+    use crate::module::fixtures;
+
+    const NAMES: &[(&str, &str)] = &[
+        ("ℕ", "Datatype"),
+        ("double", "Function"),
+        ("go", "Function"),
+        ("after", "Function"),
+        ("n", "Bound"),
+    ];
+
+    /// Text of the clause whose `where` block contains each definition of `go` and `after`.
+    fn local_clauses(source: &str) -> Vec<(usize, Option<String>)> {
+        let document = Html::parse_document(&fixtures::render("M", source, NAMES));
+        let code = Selector::parse("pre.Agda").unwrap();
+        let tokens = Tokens::new(&document, &code);
+        let scopes = Scopes::new(&tokens);
+
+        tokens
+            .iter()
+            .filter(|(_, token)| token.text == "go" || token.text == "after")
+            .filter(|(index, _)| tokens.line_start(*index) == *index)
+            .map(|(index, token)| {
+                let clause = scopes.local_clause(index);
+                let text = clause.and_then(|clause| tokens.get(clause));
+                (token.line, text.map(|token| token.text.clone()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn where_block_indented_past_where() {
+        let source = indoc! {"
+            module M where
+            double : ℕ → ℕ
+            double n = go n
+              where
+                go : ℕ → ℕ
+                go n = n
+            after : ℕ
+        "};
+
+        assert_eq!(
+            local_clauses(source),
+            [
+                (5, Some("double".into())),
+                (6, Some("double".into())),
+                (7, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn where_block_at_column_of_where() {
+        let source = indoc! {"
+            module M where
+            double : ℕ → ℕ
+            double n = go n
+              where
+              go : ℕ → ℕ
+              go n = n
+            after : ℕ
+        "};
+
+        assert_eq!(
+            local_clauses(source),
+            [
+                (5, Some("double".into())),
+                (6, Some("double".into())),
+                (7, None)
+            ]
+        );
+    }
+}
@@ -0,0 +1,284 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// A single token of highlighted Agda source.
+///
+/// Agda renders every highlighted token as an anchor inside `<pre class="Agda">`,
+/// with plain text nodes (mostly whitespace) in between. Both become tokens.
+#[derive(Debug)]
+pub struct Token<'doc> {
+    pub text: String,
+    pub id: Option<&'doc str>,
+    pub href: Option<&'doc str>,
+    pub classes: Vec<&'doc str>,
+    /// The anchor rendering a highlighted token.
+    pub element: Option<ElementRef<'doc>>,
+    /// Line of the first character of this token, starting at 1.
+    pub line: usize,
+    /// Column of the first character of this token, starting at 1.
+    pub column: usize,
+}
+
+impl Token<'_> {
+    pub fn is_whitespace(&self) -> bool {
+        self.text.chars().all(char::is_whitespace)
+    }
+
+    /// The token as rendered, with escapes and the anchor of highlighted tokens.
+    pub fn html(&self) -> String {
+        match self.element {
+            Some(element) => element.html(),
+            None => self
+                .text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+        }
+    }
+
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes.contains(&class)
+    }
+
+    pub fn is_symbol(&self, symbol: &str) -> bool {
+        self.has_class("Symbol") && self.text == symbol
+    }
+
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        self.has_class("Keyword") && self.text == keyword
+    }
+
+    /// Change in bracket nesting depth caused by this token.
+    pub fn nesting(&self) -> isize {
+        if !self.has_class("Symbol") {
+            return 0;
+        }
+        match self.text.as_str() {
+            "(" | "{" | "{{" | "⦃" | "⦇" => 1,
+            ")" | "}" | "}}" | "⦄" | "⦈" => -1,
+            _ => 0,
+        }
+    }
+}
+
+/// All tokens of the Agda code blocks in a rendered module, in document order.
+#[derive(Debug)]
+pub struct Tokens<'doc> {
+    tokens: Vec<Token<'doc>>,
+    by_id: HashMap<&'doc str, usize>,
+}
+
+impl<'doc> Tokens<'doc> {
+    pub fn new(document: &'doc Html, code: &Selector) -> Self {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut by_id = HashMap::new();
+        let mut line = 1;
+        let mut column;
+
+        for (number, block) in document.select(code).enumerate() {
+            // In modules rendered to Markdown, prose separates the code blocks.
+            line += block
+                .prev_siblings()
+                .take_while(|sibling| {
+                    !ElementRef::wrap(*sibling).is_some_and(|element| code.matches(&element))
+                })
+                .filter_map(|sibling| sibling.value().as_text())
+                .map(|text| text.matches('\n').count())
+                .sum::<usize>();
+            column = 1;
+            let mut offset = 0;
+            let mut synchronized = number > 0 || line > 1;
+
+            for child in block.children() {
+                let mut token = match child.value() {
+                    Node::Text(text) => Token {
+                        text: text.to_string(),
+                        id: None,
+                        href: None,
+                        classes: Vec::new(),
+                        element: None,
+                        line,
+                        column,
+                    },
+                    Node::Element(element) => {
+                        let Some(element_ref) = ElementRef::wrap(child) else {
+                            continue;
+                        };
+                        Token {
+                            text: element_ref.text().collect(),
+                            id: element.id(),
+                            href: element.attr("href"),
+                            classes: element.classes().collect(),
+                            element: Some(element_ref),
+                            line,
+                            column,
+                        }
+                    }
+                    _ => continue,
+                };
+
+                // Anchor IDs are offsets into the source file. HTML parsers drop
+                // a line break right at the start of a `<pre>`, which shows up as
+                // a single missing character before the first anchor of a file.
+                if !synchronized {
+                    if let Some(id) = token.id.and_then(|id| id.parse::<usize>().ok()) {
+                        synchronized = true;
+                        if id == offset + 2 {
+                            line += 1;
+                            token.line += 1;
+                            for previous in &mut tokens {
+                                previous.line += 1;
+                            }
+                        }
+                    }
+                }
+                offset += token.text.chars().count();
+
+                for c in token.text.chars() {
+                    if c == '\n' {
+                        line += 1;
+                        column = 1;
+                    } else {
+                        column += 1;
+                    }
+                }
+
+                if let Some(id) = token.id {
+                    by_id.insert(id, tokens.len());
+                }
+                tokens.push(token);
+            }
+        }
+
+        Self { tokens, by_id }
+    }
+
+    /// Iterate over all tokens along with their index.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Token<'doc>)> {
+        self.tokens.iter().enumerate()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Token<'doc>> {
+        self.tokens.get(index)
+    }
+
+    /// Index of the token with the given anchor ID.
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.by_id.get(id).copied()
+    }
+
+    /// The closest non-whitespace token after `index`, along with its index.
+    pub fn next(&self, index: usize) -> Option<(usize, &Token<'doc>)> {
+        self.iter()
+            .skip(index + 1)
+            .find(|(_, token)| !token.is_whitespace())
+    }
+
+    /// Iterate backwards over the tokens before `index`, along with their index.
+    pub fn before(&self, index: usize) -> impl Iterator<Item = (usize, &Token<'doc>)> {
+        self.tokens[..index].iter().enumerate().rev()
+    }
+
+    /// The closest non-whitespace token before `index`, along with its index.
+    pub fn previous(&self, index: usize) -> Option<(usize, &Token<'doc>)> {
+        self.before(index).find(|(_, token)| !token.is_whitespace())
+    }
+
+    /// Index of the first non-whitespace token on the line of the token at `index`.
+    pub fn line_start(&self, index: usize) -> usize {
+        let line = self.tokens[index].line;
+        self.before(index + 1)
+            .take_while(|(_, token)| token.line == line)
+            .filter(|(_, token)| !token.is_whitespace())
+            .last()
+            .map_or(index, |(start, _)| start)
+    }
+
+    /// Column of the first non-whitespace token on the line of the token at `index`.
+    pub fn indentation(&self, index: usize) -> usize {
+        self.tokens[self.line_start(index)].column
+    }
+
+    /// Iterate over the tokens that follow `index` in the same declaration.
+    ///
+    /// A declaration ends before the first non-whitespace token on a later line
+    /// that is not indented further than the line the declaration started on.
+    pub fn declaration_after(&self, index: usize) -> impl Iterator<Item = &Token<'doc>> {
+        let start = &self.tokens[index];
+        let indentation = self.indentation(index);
+
+        self.tokens[index + 1..].iter().take_while(move |token| {
+            token.is_whitespace() || token.line == start.line || token.column > indentation
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Line and column of the token with the given anchor id.
+    fn location(html: &str, id: &str) -> (usize, usize) {
+        let document = Html::parse_document(html);
+        let code = Selector::parse("pre.Agda").unwrap();
+        let tokens = Tokens::new(&document, &code);
+        let token = tokens
+            .position(id)
+            .and_then(|index| tokens.get(index))
+            .unwrap();
+        (token.line, token.column)
+    }
+
+    #[test]
+    fn locates_tokens() {
+        let html = concat!(
+            r#"<pre class="Agda"><a id="1" class="Keyword">module</a> <a id="8" href="M.html" class="Module">M</a> <a id="10" class="Keyword">where</a>"#,
+            "\n\n",
+            r#"<a id="17" href="M.html#17" class="Function">x</a> <a id="19" class="Symbol">:</a>"#,
+            "</pre>",
+        );
+
+        assert_eq!(location(html, "1"), (1, 1));
+        assert_eq!(location(html, "8"), (1, 8));
+        assert_eq!(location(html, "17"), (3, 1));
+        assert_eq!(location(html, "19"), (3, 3));
+    }
+
+    #[test]
+    fn locates_tokens_after_leading_line_break() {
+        // The parser drops the line break the source starts with.
+        let html = concat!(
+            r#"<pre class="Agda">"#,
+            "\n  ",
+            r#"<a id="4" class="Keyword">module</a> <a id="11" href="M.html" class="Module">M</a>"#,
+            "\n",
+            r#"<a id="13" href="M.html#13" class="Function">x</a>"#,
+            "</pre>",
+        );
+
+        assert_eq!(location(html, "4"), (2, 3));
+        assert_eq!(location(html, "11"), (2, 10));
+        assert_eq!(location(html, "13"), (3, 1));
+    }
+
+    #[test]
+    fn locates_tokens_between_markdown_prose() {
+        let markdown = concat!(
+            "# Title\n\nIntro.\n\n",
+            r#"<pre class="Agda"><a id="1" class="Keyword">module</a> <a id="8" href="M.html" class="Module">M</a>"#,
+            "\n</pre>\n\nMore prose.\n\n",
+            r#"<pre class="Agda"><a id="40" href="M.html#40" class="Function">y</a> <a id="42" class="Symbol">:</a>"#,
+            "\n</pre>\n",
+        );
+
+        assert_eq!(location(markdown, "1"), (5, 1));
+        assert_eq!(location(markdown, "8"), (5, 8));
+        assert_eq!(location(markdown, "40"), (10, 1));
+        assert_eq!(location(markdown, "42"), (10, 3));
+    }
+}
@@ -0,0 +1,132 @@
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::OutputWriter;
+use crate::{
+    module::{ItemKind, Position},
+    pipeline::Output,
+};
+
+/// Lists what a library trusts without Agda checking it: postulates,
+/// pragmas turning off checks and modules that are not `--safe`.
+#[derive(Debug)]
+pub struct AuditOutput<W> {
+    writer: W,
+    json: bool,
+}
+
+impl<W> AuditOutput<W> {
+    pub fn new(writer: W, json: bool) -> Self {
+        Self { writer, json }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum AuditKind {
+    /// A module not checked with `--safe`.
+    UnsafeModule,
+    Postulate,
+    /// A pragma like `TERMINATING`.
+    Pragma,
+}
+
+#[derive(Debug, Serialize)]
+struct AuditEntry {
+    module: String,
+    kind: AuditKind,
+    /// Name of the pragma, for pragmas.
+    pragma: Option<String>,
+    /// Qualified name of the module, postulate or definition the pragma applies to.
+    name: Option<String>,
+    href: String,
+    /// Link to the entry's site in the plaintext report.
+    #[serde(skip)]
+    location: String,
+    position: Option<Position>,
+}
+
+impl AuditEntry {
+    /// Label of the entry in the plaintext report.
+    fn label(&self) -> &str {
+        match self.kind {
+            AuditKind::UnsafeModule => "unsafe-module",
+            AuditKind::Postulate => "postulate",
+            AuditKind::Pragma => self.pragma.as_deref().unwrap_or("pragma"),
+        }
+    }
+}
+
+impl<W> OutputWriter for AuditOutput<W>
+where
+    W: Write,
+{
+    fn write_output(&mut self, output: Output) -> Result<()> {
+        let mut modules: Vec<_> = output.into_iter().collect();
+        modules.sort_by(|a, b| a.module.name.cmp(&b.module.name));
+
+        let mut entries = Vec::new();
+        for item in &modules {
+            let path = item.canonical_rendered_path()?;
+            let path = path.display();
+            let module = &item.module;
+
+            if !module.has_option("--safe") {
+                entries.push(AuditEntry {
+                    module: module.name.clone(),
+                    kind: AuditKind::UnsafeModule,
+                    pragma: None,
+                    name: Some(module.name.clone()),
+                    href: format!("{}.html", module.name),
+                    location: format!("file:///{path}"),
+                    position: None,
+                });
+            }
+
+            let postulates = module
+                .items
+                .iter()
+                .filter(|item| item.kind == ItemKind::Postulate)
+                .map(|postulate| AuditEntry {
+                    module: module.name.clone(),
+                    kind: AuditKind::Postulate,
+                    pragma: None,
+                    name: Some(postulate.qualified_name(&module.name)),
+                    href: format!("{}.html#{}", module.name, postulate.id),
+                    location: format!("file:///{path}#{id}", id = postulate.id),
+                    position: postulate.position,
+                });
+            let pragmas = module.trust_pragmas.iter().map(|pragma| AuditEntry {
+                module: module.name.clone(),
+                kind: AuditKind::Pragma,
+                pragma: Some(pragma.pragma.clone()),
+                name: pragma.definition.clone(),
+                href: format!("{}.html#{}", module.name, pragma.id),
+                location: format!("file:///{path}#{id}", id = pragma.id),
+                position: Some(pragma.position),
+            });
+            entries.extend(postulates.chain(pragmas));
+        }
+
+        if self.json {
+            serde_json::to_writer(&mut self.writer, &entries)
+                .context("Failed to write audit report")?;
+            return Ok(());
+        }
+
+        for entry in entries {
+            writeln!(
+                &mut self.writer,
+                r"{location} {label} {name}",
+                location = entry.location,
+                label = entry.label(),
+                name = entry.name.as_deref().unwrap_or(&entry.module),
+            )
+            .with_context(|| format!("Failed to write audit entry of {}", entry.module))?;
+        }
+
+        Ok(())
+    }
+}
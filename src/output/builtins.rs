@@ -0,0 +1,64 @@
+use std::{collections::HashMap, io::Write};
+
+use anyhow::{Context, Result};
+
+use super::OutputWriter;
+use crate::pipeline::Output;
+
+/// Lists the definitions bound to builtins by `BUILTIN` pragmas.
+#[derive(Debug)]
+pub struct BuiltinsOutput<W> {
+    writer: W,
+}
+
+impl<W> BuiltinsOutput<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<W> OutputWriter for BuiltinsOutput<W>
+where
+    W: Write,
+{
+    fn write_output(&mut self, output: Output) -> Result<()> {
+        let modules: Vec<_> = output.into_iter().collect();
+
+        let mut paths = HashMap::new();
+        for item in &modules {
+            paths.insert(item.module.name.as_str(), item.canonical_rendered_path()?);
+        }
+
+        let mut builtins: Vec<_> = modules
+            .iter()
+            .flat_map(|item| {
+                item.module
+                    .builtins
+                    .iter()
+                    .map(move |builtin| (builtin, &item.module.name))
+            })
+            .collect();
+        builtins.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+
+        for (builtin, module_name) in builtins {
+            // Builtins may be bound to definitions of modules that were not indexed.
+            let path = match paths.get(builtin.target.module.as_str()) {
+                Some(path) => path.clone(),
+                None => paths[module_name.as_str()]
+                    .with_file_name(format!("{}.html", builtin.target.module)),
+            };
+
+            writeln!(
+                &mut self.writer,
+                r"file:///{path}#{id} {name} {definition}",
+                path = path.display(),
+                id = builtin.target.id,
+                name = builtin.name,
+                definition = builtin.definition,
+            )
+            .with_context(|| format!("Failed to write builtin {}", builtin.name))?;
+        }
+
+        Ok(())
+    }
+}
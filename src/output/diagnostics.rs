@@ -0,0 +1,87 @@
+use std::io::Write;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use super::OutputWriter;
+use crate::{module::Position, pipeline::Output};
+
+/// Lists the problems Agda highlighted in the rendered modules.
+///
+/// Fails after writing them if there are any, since modules with problems do not check.
+#[derive(Debug)]
+pub struct DiagnosticsOutput<W> {
+    writer: W,
+    json: bool,
+}
+
+impl<W> DiagnosticsOutput<W> {
+    pub fn new(writer: W, json: bool) -> Self {
+        Self { writer, json }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DiagnosticEntry<'m> {
+    module: &'m str,
+    class: &'m str,
+    href: String,
+    position: Position,
+    text: &'m str,
+}
+
+impl<W> OutputWriter for DiagnosticsOutput<W>
+where
+    W: Write,
+{
+    fn write_output(&mut self, output: Output) -> Result<()> {
+        let mut modules: Vec<_> = output.into_iter().collect();
+        modules.sort_by(|a, b| a.module.name.cmp(&b.module.name));
+
+        let mut entries = Vec::new();
+        for item in &modules {
+            let path = item.canonical_rendered_path()?;
+            let module = &item.module;
+
+            for diagnostic in &module.diagnostics {
+                let anchor = diagnostic
+                    .id
+                    .as_ref()
+                    .map(|id| format!("#{id}"))
+                    .unwrap_or_default();
+
+                if !self.json {
+                    writeln!(
+                        &mut self.writer,
+                        r"file:///{path}{anchor} {module}:{position} {class} {text}",
+                        path = path.display(),
+                        module = module.name,
+                        position = diagnostic.position,
+                        class = diagnostic.class,
+                        text = diagnostic.text,
+                    )
+                    .with_context(|| format!("Failed to write diagnostic of {}", module.name))?;
+                }
+
+                entries.push(DiagnosticEntry {
+                    module: &module.name,
+                    class: &diagnostic.class,
+                    href: format!("{}.html{anchor}", module.name),
+                    position: diagnostic.position,
+                    text: &diagnostic.text,
+                });
+            }
+        }
+
+        if self.json {
+            serde_json::to_writer(&mut self.writer, &entries)
+                .context("Failed to write diagnostics")?;
+        }
+
+        if !entries.is_empty() {
+            bail!("Agda reported {} problems", entries.len());
+        }
+
+        Ok(())
+    }
+}
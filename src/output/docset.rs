@@ -6,10 +6,11 @@ use std::{
 };
 
 use anyhow::{bail, Context, Result};
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
 use rusqlite::params;
 
 use super::{Output, OutputWriter};
-use crate::module::{Item, Module};
+use crate::module::{transliterate, Item, ItemKind, Module, Telescope};
 
 #[derive(Debug)]
 pub struct DocsetOutput {
@@ -96,7 +97,7 @@ impl DocsetOutput {
     }
 
     fn write_icon(&self) -> Result<()> {
-        const AGDA_SVG: &'static [u8] = include_bytes!("../../resources/Agda.svg");
+        const AGDA_SVG: &[u8] = include_bytes!("../../resources/Agda.svg");
 
         let icon_path = self.docset_dir().join("icon.svg");
 
@@ -136,13 +137,21 @@ impl IndexDatabase {
         })
     }
 
+    /// Create the index table read by Dash and Zeal.
+    ///
+    /// They only read `name`, `type` and `path`; the remaining columns are
+    /// for external tooling querying the database.
     pub fn create_schema(&self) -> Result<()> {
         self.connection.execute(
             r"CREATE TABLE searchIndex(
                 id INTEGER PRIMARY KEY,
                 name TEXT,
                 type TEXT,
-                path TEXT
+                path TEXT,
+                signature TEXT,
+                fixity TEXT,
+                deprecation TEXT,
+                doc TEXT
             );",
             rusqlite::params![],
         )?;
@@ -172,17 +181,22 @@ impl OutputWriter for IndexDatabase {
         let tsx = self.connection.transaction()?;
         {
             let mut insert_item = tsx.prepare(
-                r"INSERT OR IGNORE INTO searchIndex(name, type, path) VALUES (?1, ?2, ?3);",
+                r"INSERT OR IGNORE INTO searchIndex(name, type, path, signature, fixity, deprecation, doc) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7);",
             )?;
 
             for item in output.into_iter() {
+                let rendered_path = item.rendered_path();
+                let is_markdown = item.is_markdown();
                 let Module {
                     name: module_name,
+                    summary,
+                    telescope,
                     items,
+                    ..
                 } = item.module;
 
                 let module_path = ModulePath(
-                    item.source_path
+                    rendered_path
                         .file_name()
                         .ok_or_else(|| anyhow::anyhow!("No file name on source module"))?,
                 );
@@ -194,7 +208,17 @@ impl OutputWriter for IndexDatabase {
                         item.source_path.display(),
                         module_target_path.display()
                     );
-                    std::fs::copy(&item.source_path, &module_target_path).with_context(|| {
+                    let copied = fs::read_to_string(&item.source_path).and_then(|content| {
+                        let page = if is_markdown {
+                            markdown_page(&module_name, &content)
+                        } else {
+                            content
+                        };
+                        let page =
+                            annotate_page(&page, summary.as_deref(), telescope.as_ref(), &items);
+                        fs::write(&module_target_path, page)
+                    });
+                    copied.with_context(|| {
                         format!(
                             "Failed to copy {module_name} to {}",
                             module_target_path.display()
@@ -202,17 +226,48 @@ impl OutputWriter for IndexDatabase {
                     })?;
                 }
 
-                insert_item.execute(params![module_name, "Module", module_path])?;
-
-                for Item { id, identifier, classes } in items {
-                    let fqn = format!("{module_name}.{identifier}");
-                    insert_item
-                        .execute(params![
-                            &fqn,
-                            "Function",
-                            module_path.anchored_index_path(&id)
-                        ])
-                        .with_context(|| format!("Failed to write entry {fqn}"))?;
+                insert_item.execute(params![
+                    module_name,
+                    "Module",
+                    module_path,
+                    telescope.map(|telescope| telescope.text),
+                    None::<String>,
+                    None::<String>,
+                    summary,
+                ])?;
+
+                for item in &items {
+                    // Aliases lead to the definition they rename.
+                    let path = match &item.alias_of {
+                        Some(alias) => alias.target.to_string(),
+                        None => module_path.anchored_index_path(&item.id),
+                    };
+                    let names = [&item.identifier].into_iter().chain(&item.aliases);
+                    // Every name is also indexed as typed with the input method.
+                    let qualified_names = names
+                        .map(|name| item.qualify(&module_name, name))
+                        .flat_map(|fqn| {
+                            let transliterated = transliterate(&fqn);
+                            [Some(fqn), transliterated].into_iter().flatten()
+                        });
+                    for fqn in qualified_names {
+                        insert_item
+                            .execute(params![
+                                &fqn,
+                                entry_type(item.kind),
+                                path,
+                                item.signature,
+                                item.fixity.as_ref().map(ToString::to_string),
+                                item.deprecation
+                                    .as_ref()
+                                    .map(|deprecation| &deprecation.message),
+                                item.alias_of
+                                    .as_ref()
+                                    .map(|alias| format!("Alias of {}", alias.name))
+                                    .or(item.doc.clone()),
+                            ])
+                            .with_context(|| format!("Failed to write entry {fqn}"))?;
+                    }
                 }
             }
         }
@@ -242,6 +297,184 @@ impl OutputWriter for DocsetOutput {
     }
 }
 
+/// Wrap a module rendered to Markdown in a page the docset can display.
+///
+/// The prose is rendered to HTML, while the code blocks Agda highlighted are
+/// HTML already and kept as they are, even if they are still fenced.
+fn markdown_page(module_name: &str, markdown: &str) -> String {
+    let options = Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+        | Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH;
+
+    let mut events = Vec::new();
+    let mut code_block = None;
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => code_block = Some((kind, String::new())),
+            Event::Text(text) if code_block.is_some() => {
+                if let Some((_, code)) = &mut code_block {
+                    code.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                let Some((kind, code)) = code_block.take() else {
+                    continue;
+                };
+                if code.trim_start().starts_with(r#"<pre class="Agda">"#) {
+                    events.push(Event::Html(code.into()));
+                } else {
+                    events.push(Event::Start(Tag::CodeBlock(kind)));
+                    events.push(Event::Text(code.into()));
+                    events.push(Event::End(TagEnd::CodeBlock));
+                }
+            }
+            event => events.push(event),
+        }
+    }
+
+    let mut body = String::new();
+    html::push_html(&mut body, events.into_iter());
+
+    format!(
+        indoc::indoc! {r#"
+            <!DOCTYPE HTML>
+            <html><head><meta charset="utf-8"><title>{module_name}</title><link rel="stylesheet" href="Agda.css"></head>
+            <body>
+            {body}
+            </body></html>
+        "#},
+        module_name = module_name,
+        body = body,
+    )
+}
+
+/// Show what the index knows about the module and its definitions on its page.
+///
+/// The module's summary and parameters head the page. Each defining occurrence
+/// gets a tooltip with the signature, fixity, doc comment and deprecation of its
+/// item. Fixities and deprecations are declared elsewhere, so they are also added
+/// like a comment at the end of the line, and deprecated definitions are struck through.
+fn annotate_page(
+    page: &str,
+    summary: Option<&str>,
+    telescope: Option<&Telescope>,
+    items: &[Item],
+) -> String {
+    let mut insertions = Vec::new();
+
+    let header: Vec<String> = summary
+        .map(|summary| format!("<p>{}</p>", escape(summary)))
+        .into_iter()
+        .chain(telescope.map(|telescope| {
+            format!(
+                "<p>Parameters: <code>{}</code></p>",
+                escape(&telescope.text)
+            )
+        }))
+        .collect();
+    if let Some(body) = page.find("<body>").filter(|_| !header.is_empty()) {
+        insertions.push((
+            body + "<body>".len(),
+            format!(r#"<div class="ModuleInfo">{}</div>"#, header.concat()),
+        ));
+    }
+
+    // Aliases are anchored where they rename a definition, not where it is defined.
+    for item in items.iter().filter(|item| item.alias_of.is_none()) {
+        let anchor = format!(r#"<a id="{}""#, item.id);
+        let Some(start) = page.find(&anchor) else {
+            continue;
+        };
+
+        let fixity = item.fixity.as_ref().map(ToString::to_string);
+        let deprecation = item
+            .deprecation
+            .as_ref()
+            .map(|deprecation| format!("Deprecated: {}", single_line(&deprecation.message)));
+        let tooltip: Vec<String> = [
+            item.signature
+                .as_ref()
+                .map(|signature| format!("{} : {signature}", item.identifier)),
+            fixity.clone(),
+            item.doc.clone(),
+            deprecation.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if tooltip.is_empty() {
+            continue;
+        }
+
+        let style = match deprecation {
+            Some(_) => r#" style="text-decoration: line-through""#,
+            None => "",
+        };
+        insertions.push((
+            start + anchor.len(),
+            format!(
+                r#" title="{tooltip}"{style}"#,
+                tooltip = escape(&tooltip.join("\n")).replace('\n', "&#10;")
+            ),
+        ));
+
+        let notes: Vec<String> = [fixity, deprecation].into_iter().flatten().collect();
+        if notes.is_empty() {
+            continue;
+        }
+        let line_end = page[start..]
+            .find("</a>")
+            .and_then(|end| {
+                page[start + end..]
+                    .find('\n')
+                    .map(|newline| start + end + newline)
+            })
+            .unwrap_or(page.len());
+        insertions.push((
+            line_end,
+            format!(
+                r#" <span class="Note" style="color: darkred">-- {}</span>"#,
+                escape(&notes.join("; "))
+            ),
+        ));
+    }
+
+    let mut page = page.to_string();
+    insertions.sort_by_key(|&(position, _)| std::cmp::Reverse(position));
+    for (position, text) in insertions {
+        page.insert_str(position, &text);
+    }
+    page
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Dash entry type used to index items of the given kind.
+fn entry_type(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::Module => "Module",
+        ItemKind::Function => "Function",
+        ItemKind::Datatype => "Type",
+        ItemKind::Constructor => "Constructor",
+        ItemKind::Record => "Struct",
+        ItemKind::Field => "Field",
+        ItemKind::Postulate => "Constant",
+        ItemKind::Primitive => "Builtin",
+        ItemKind::Macro => "Macro",
+        ItemKind::PatternSynonym => "Notation",
+        ItemKind::Generalizable => "Variable",
+    }
+}
+
 struct ModulePath<'file>(&'file OsStr);
 
 impl<'file> ModulePath<'file> {
@@ -252,8 +485,100 @@ impl<'file> ModulePath<'file> {
 }
 
 impl rusqlite::ToSql for ModulePath<'_> {
-    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput> {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
         let module_path_str = self.0.to_str().expect("Invalid module path");
         module_path_str.to_sql()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn renders_markdown_pages() {
+        let markdown = indoc! {r#"
+            ---
+            title: M
+            ---
+            # Operators
+
+            Both `_*_` and 2 * 3 are [defined](#1) below.
+
+            ```agda
+            <pre class="Agda"><a id="1" class="Keyword">module</a></pre>
+            ```
+
+            ```
+            plain code
+            ```
+        "#};
+        let page = markdown_page("M", markdown);
+        let body = &page[page.find("<body>").unwrap()..];
+
+        assert!(!body.contains("title: M"));
+        assert!(body.contains("<h1>Operators</h1>"));
+        assert!(body.contains(
+            r##"<p>Both <code>_*_</code> and 2 * 3 are <a href="#1">defined</a> below.</p>"##
+        ));
+        assert!(body.contains(r#"<pre class="Agda"><a id="1" class="Keyword">module</a></pre>"#));
+        assert!(body.contains("<pre><code>plain code\n</code></pre>"));
+    }
+
+    #[test]
+    fn annotates_definitions_on_pages() {
+        let source = indoc! {r#"
+            -- Natural numbers.
+            module M where
+
+            data ℕ : Set where
+              zero : ℕ
+              suc : ℕ → ℕ
+
+            -- Addition.
+            _+_ : ℕ → ℕ → ℕ
+            zero + m = m
+            suc n + m = suc (n + m)
+
+            infixl 6 _+_
+
+            old : ℕ
+            old = zero
+            {-# WARNING_ON_USAGE old "Use zero" #-}
+        "#};
+        let names = [
+            ("ℕ", "Datatype"),
+            ("zero", "InductiveConstructor"),
+            ("suc", "InductiveConstructor"),
+            ("_+_", "Function Operator"),
+            ("old", "Function"),
+            ("n", "Bound"),
+            ("m", "Bound"),
+        ];
+        let page = crate::module::fixtures::render("M", source, &names);
+        let module = crate::module::ModuleParser::new()
+            .parse_module(&page, false)
+            .unwrap();
+        let page = annotate_page(
+            &page,
+            module.summary.as_deref(),
+            module.telescope.as_ref(),
+            &module.items,
+        );
+
+        assert!(page.contains(r#"<body><div class="ModuleInfo"><p>Natural numbers.</p></div>"#));
+        assert!(page.contains(
+            r#"<a id="95" title="_+_ : ℕ → ℕ → ℕ&#10;infixl 6&#10;Addition." href="M.html#95""#
+        ));
+        assert!(page.contains(
+            r#"<a id="42" title="ℕ : Set" href="M.html#42" class="Datatype">ℕ</a> <a id="44""#
+        ));
+        assert!(page.contains(r#"<span class="Note" style="color: darkred">-- infixl 6</span>"#));
+        assert!(page.contains(
+            r#"title="old : ℕ&#10;Deprecated: Use zero" style="text-decoration: line-through""#
+        ));
+        assert!(page.contains("-- Deprecated: Use zero</span>\n<a id=\"171\""));
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
+
+use anyhow::Result;
+
+use super::OutputWriter;
+use crate::pipeline::Output;
+
+/// Writes the import graph of all modules in Graphviz DOT format.
+#[derive(Debug)]
+pub struct DotOutput<W> {
+    writer: W,
+    cluster: bool,
+}
+
+impl<W> DotOutput<W> {
+    pub fn new(writer: W, cluster: bool) -> Self {
+        Self { writer, cluster }
+    }
+}
+
+/// Quote a module name as a DOT identifier.
+fn quoted(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Namespace of a module, i.e. its name without the last component.
+fn namespace(name: &str) -> &str {
+    name.rsplit_once('.').map_or("", |(namespace, _)| namespace)
+}
+
+impl<W> OutputWriter for DotOutput<W>
+where
+    W: Write,
+{
+    fn write_output(&mut self, output: Output) -> Result<()> {
+        let imports: BTreeMap<String, Vec<String>> = output
+            .into_iter()
+            .map(|item| (item.module.name, item.module.imports))
+            .collect();
+
+        let modules: BTreeSet<&str> = imports
+            .iter()
+            .flat_map(|(name, imports)| [name].into_iter().chain(imports))
+            .map(String::as_str)
+            .collect();
+
+        writeln!(self.writer, "digraph imports {{")?;
+
+        if self.cluster {
+            let mut namespaces: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+            for module in &modules {
+                namespaces
+                    .entry(namespace(module))
+                    .or_default()
+                    .push(module);
+            }
+
+            for (namespace, modules) in namespaces {
+                if namespace.is_empty() {
+                    for module in modules {
+                        writeln!(self.writer, "    {};", quoted(module))?;
+                    }
+                    continue;
+                }
+
+                writeln!(
+                    self.writer,
+                    "    subgraph {} {{",
+                    quoted(&format!("cluster_{namespace}"))
+                )?;
+                writeln!(self.writer, "        label = {};", quoted(namespace))?;
+                for module in modules {
+                    writeln!(self.writer, "        {};", quoted(module))?;
+                }
+                writeln!(self.writer, "    }}")?;
+            }
+        } else {
+            for module in &modules {
+                writeln!(self.writer, "    {};", quoted(module))?;
+            }
+        }
+
+        for (module, imports) in &imports {
+            for import in imports {
+                writeln!(self.writer, "    {} -> {};", quoted(module), quoted(import))?;
+            }
+        }
+
+        writeln!(self.writer, "}}")?;
+
+        Ok(())
+    }
+}
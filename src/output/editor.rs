@@ -0,0 +1,87 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+
+use super::{plain::write_entry, OutputWriter};
+use crate::{module::Module, pipeline::Output};
+
+/// Extensions of Agda source files, in the order they are looked for.
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "agda",
+    "lagda.md",
+    "lagda",
+    "lagda.tex",
+    "lagda.rst",
+    "lagda.org",
+    "lagda.typ",
+    "lagda.tree",
+];
+
+/// Lists definitions by their location in the Agda sources, for opening them in an editor.
+#[derive(Debug)]
+pub struct EditorOutput<W> {
+    writer: W,
+    source_root: PathBuf,
+}
+
+impl<W> EditorOutput<W> {
+    pub fn new(writer: W, source_root: PathBuf) -> Self {
+        Self {
+            writer,
+            source_root,
+        }
+    }
+
+    /// Path of the source file of a module below the source root.
+    ///
+    /// Falls back to `.agda` (or `.lagda.md` for modules rendered to Markdown)
+    /// if no source file exists.
+    fn source_path(&self, module_name: &str, is_markdown: bool) -> PathBuf {
+        let base = self.source_root.join(module_name.replace('.', "/"));
+        let with_extension = |ext: &str| -> PathBuf {
+            let mut path = base.clone().into_os_string();
+            path.push(".");
+            path.push(ext);
+            path.into()
+        };
+
+        SOURCE_EXTENSIONS
+            .iter()
+            .map(|ext| with_extension(ext))
+            .find(|path| Path::exists(path))
+            .unwrap_or_else(|| with_extension(if is_markdown { "lagda.md" } else { "agda" }))
+    }
+}
+
+impl<W> OutputWriter for EditorOutput<W>
+where
+    W: Write,
+{
+    fn write_output(&mut self, output: Output) -> Result<()> {
+        for item in output.into_iter() {
+            let path = self.source_path(&item.module.name, item.is_markdown());
+
+            let Module {
+                name: module_name,
+                items,
+                ..
+            } = item.module;
+
+            for item in items {
+                // The position of an alias is where it renames a definition of another
+                // module, whose position is not known.
+                let Some(position) = item.position.filter(|_| item.alias_of.is_none()) else {
+                    continue;
+                };
+
+                let location = format!("{path}:{position}", path = path.display());
+                write_entry(&mut self.writer, location, &module_name, &item)?;
+            }
+        }
+
+        Ok(())
+    }
+}
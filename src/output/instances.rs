@@ -0,0 +1,66 @@
+use std::io::Write;
+
+use anyhow::{Context, Result};
+
+use super::{definitions_named, OutputWriter};
+use crate::pipeline::Output;
+
+/// Lists the instances of any record type of a given name.
+#[derive(Debug)]
+pub struct InstancesOutput<W> {
+    name: String,
+    writer: W,
+}
+
+impl<W> InstancesOutput<W> {
+    pub fn new(name: String, writer: W) -> Self {
+        Self { name, writer }
+    }
+}
+
+impl<W> OutputWriter for InstancesOutput<W>
+where
+    W: Write,
+{
+    fn write_output(&mut self, output: Output) -> Result<()> {
+        let mut modules: Vec<_> = output.into_iter().collect();
+        modules.sort_by(|a, b| a.module.name.cmp(&b.module.name));
+
+        let classes = definitions_named(&modules, &self.name)?;
+
+        for item in &modules {
+            let path = item.canonical_rendered_path()?;
+            let module = &item.module;
+
+            for instance in &module.items {
+                if !instance
+                    .instance_of
+                    .as_ref()
+                    .is_some_and(|class| classes.contains(class))
+                {
+                    continue;
+                }
+
+                write!(
+                    &mut self.writer,
+                    r"file:///{path}#{id} {name}",
+                    path = path.display(),
+                    id = instance.id,
+                    name = instance.qualified_name(&module.name),
+                )
+                .and_then(|()| match &instance.signature {
+                    Some(signature) => writeln!(&mut self.writer, " : {signature}"),
+                    None => writeln!(&mut self.writer),
+                })
+                .with_context(|| {
+                    format!(
+                        "Failed to write instance {}.{}",
+                        module.name, instance.identifier
+                    )
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
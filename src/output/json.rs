@@ -1,14 +1,18 @@
-use std::{fmt, io::Write};
+use std::{collections::HashSet, fmt, io::Write};
 
 use anyhow::Result;
 use serde::{ser::SerializeSeq, Serialize};
 use serde_json::Serializer;
 
 use super::OutputWriter;
-use crate::{module::Module, pipeline::Output};
+use crate::{
+    module::{Binder, Deprecation, Fixity, ItemKind, Module, Position, Snippet, Visibility},
+    pipeline::Output,
+};
 
 pub struct JsonOutput<W> {
     serializer: Serializer<W>,
+    nest_constructors: bool,
 }
 
 impl<W> fmt::Debug for JsonOutput<W> {
@@ -21,9 +25,12 @@ impl<W> JsonOutput<W>
 where
     W: Write,
 {
-    pub fn new(writer: W) -> Self {
+    pub fn new(writer: W, nest_constructors: bool) -> Self {
         let serializer = Serializer::new(writer);
-        Self { serializer }
+        Self {
+            serializer,
+            nest_constructors,
+        }
     }
 }
 
@@ -31,7 +38,48 @@ where
 struct IndexItem<'n> {
     module: &'n str,
     identifier: String,
+    qualified_name: String,
+    aliases: Vec<String>,
+    kind: ItemKind,
+    visibility: Visibility,
+    #[serde(rename = "abstract")]
+    is_abstract: bool,
+    instance: bool,
+    /// Link to the record type an instance provides.
+    instance_of: Option<String>,
     href: String, // TODO: Extract the proper href from HTML
+    position: Option<Position>,
+    signature: Option<String>,
+    /// Parameters of a module, as written.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    telescope: Option<String>,
+    /// Parameters of a module, split into binders.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    parameters: Vec<Binder>,
+    /// Flags set by the `OPTIONS` pragmas of a module.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    options: Vec<String>,
+    fixity: Option<Fixity>,
+    deprecation: Option<Deprecation>,
+    /// Source of the definition, if asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<Snippet>,
+    doc: Option<String>,
+    alias_of: Option<JsonAlias>,
+    /// Link to the definition whose `where` block declares the item.
+    parent: Option<String>,
+    /// Link to the data type or record declaring the constructor or field.
+    owner: Option<String>,
+    references: Vec<String>,
+    /// Constructors of a data type or record, if they are nested under it.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    constructors: Vec<IndexItem<'n>>,
+}
+
+#[derive(Serialize)]
+struct JsonAlias {
+    name: String,
+    href: String,
 }
 
 impl<W> OutputWriter for JsonOutput<W>
@@ -44,14 +92,104 @@ where
         let mut ser = self.serializer.serialize_seq(None)?;
 
         for item in output {
-            let Module { name, items } = item.module;
-
-            for item in items {
-                ser.serialize_element(&IndexItem {
-                    module: &name,
-                    identifier: item.identifier,
-                    href: format!("{}.html#{}", name, item.id),
-                })?;
+            let Module {
+                name,
+                summary,
+                telescope,
+                options,
+                items,
+                ..
+            } = item.module;
+            let (telescope, parameters) = match telescope {
+                Some(telescope) => (Some(telescope.text), telescope.binders),
+                None => (None, Vec::new()),
+            };
+
+            ser.serialize_element(&IndexItem {
+                module: &name,
+                identifier: name.clone(),
+                qualified_name: name.clone(),
+                aliases: Vec::new(),
+                kind: ItemKind::Module,
+                visibility: Visibility::Public,
+                is_abstract: false,
+                instance: false,
+                instance_of: None,
+                href: format!("{}.html", name),
+                position: None,
+                signature: None,
+                telescope,
+                parameters,
+                options,
+                fixity: None,
+                deprecation: None,
+                snippet: None,
+                doc: summary,
+                alias_of: None,
+                parent: None,
+                owner: None,
+                references: Vec::new(),
+                constructors: Vec::new(),
+            })?;
+
+            let entries: Vec<_> = items
+                .into_iter()
+                .map(|item| {
+                    let entry = IndexItem {
+                        module: &name,
+                        qualified_name: item.qualified_name(&name),
+                        aliases: item.aliases.clone(),
+                        identifier: item.identifier,
+                        kind: item.kind,
+                        visibility: item.visibility,
+                        is_abstract: item.is_abstract,
+                        instance: item.is_instance,
+                        instance_of: item.instance_of.as_ref().map(ToString::to_string),
+                        href: format!("{}.html#{}", name, item.id),
+                        position: item.position,
+                        signature: item.signature,
+                        telescope: None,
+                        parameters: Vec::new(),
+                        options: Vec::new(),
+                        fixity: item.fixity,
+                        deprecation: item.deprecation,
+                        snippet: item.snippet,
+                        doc: item.doc,
+                        alias_of: item.alias_of.map(|alias| JsonAlias {
+                            name: alias.name,
+                            href: alias.target.to_string(),
+                        }),
+                        parent: item.parent.map(|id| format!("{}.html#{}", name, id)),
+                        owner: item
+                            .owner
+                            .as_ref()
+                            .map(|id| format!("{}.html#{}", name, id)),
+                        references: item.references.iter().map(ToString::to_string).collect(),
+                        constructors: Vec::new(),
+                    };
+                    (item.id, item.owner, entry)
+                })
+                .collect();
+
+            // Constructors move into the entry of the data type declaring them.
+            let ids: HashSet<String> = entries.iter().map(|(id, _, _)| id.clone()).collect();
+            let (constructors, mut entries): (Vec<_>, Vec<_>) =
+                entries.into_iter().partition(|(_, owner, entry)| {
+                    self.nest_constructors
+                        && entry.kind == ItemKind::Constructor
+                        && owner.as_ref().is_some_and(|owner| ids.contains(owner))
+                });
+            for (_, owner, constructor) in constructors {
+                if let Some((_, _, owner)) = entries
+                    .iter_mut()
+                    .find(|(id, _, _)| Some(id) == owner.as_ref())
+                {
+                    owner.constructors.push(constructor);
+                }
+            }
+
+            for (_, _, entry) in entries {
+                ser.serialize_element(&entry)?;
             }
         }
 
@@ -1,14 +1,58 @@
-use anyhow::Result;
+use std::collections::HashSet;
 
+use anyhow::{bail, Result};
+
+use crate::{module::Reference, pipeline};
+
+mod audit;
+mod builtins;
+mod diagnostics;
 mod docset;
+mod dot;
+mod editor;
+mod instances;
 mod json;
 mod plain;
+mod show;
+mod uses;
 
+pub use self::audit::AuditOutput;
+pub use self::builtins::BuiltinsOutput;
+pub use self::diagnostics::DiagnosticsOutput;
 pub use self::docset::DocsetOutput;
+pub use self::dot::DotOutput;
+pub use self::editor::EditorOutput;
+pub use self::instances::InstancesOutput;
 pub use self::json::JsonOutput;
 pub use self::plain::PlainOutput;
+pub use self::show::ShowOutput;
+pub use self::uses::UsesOutput;
 pub use crate::pipeline::Output;
 
 pub trait OutputWriter {
     fn write_output(&mut self, output: Output) -> Result<()>;
 }
+
+/// The definitions of the given name, which may be qualified or not.
+fn definitions_named(modules: &[pipeline::Item], name: &str) -> Result<HashSet<Reference>> {
+    let definitions: HashSet<Reference> = modules
+        .iter()
+        .flat_map(|item| {
+            let module = &item.module;
+            module
+                .items
+                .iter()
+                .filter(|item| item.identifier == name || item.qualified_name(&module.name) == name)
+                .map(|item| Reference {
+                    module: module.name.clone(),
+                    id: item.id.clone(),
+                })
+        })
+        .collect();
+
+    if definitions.is_empty() {
+        bail!("No definition named {name}");
+    }
+
+    Ok(definitions)
+}
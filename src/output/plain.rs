@@ -1,9 +1,12 @@
-use std::io::Write;
+use std::{fmt::Display, io::Write};
 
 use anyhow::{Context, Result};
 
 use super::OutputWriter;
-use crate::{module::Module, pipeline::Output};
+use crate::{
+    module::{Item, Module},
+    pipeline::Output,
+};
 
 #[derive(Debug)]
 pub struct PlainOutput<W> {
@@ -21,33 +24,63 @@ where
     W: Write,
 {
     fn write_output(&mut self, output: Output) -> Result<()> {
-        use std::fs;
-
         for item in output.into_iter() {
-            let path = fs::canonicalize(&item.source_path).with_context(|| {
-                format!(
-                    "Failed to canonicalize path {}",
-                    &item.source_path.display()
-                )
-            })?;
+            let path = item.canonical_rendered_path()?;
 
             let Module {
                 name: module_name,
                 items,
+                ..
             } = item.module;
 
             for item in items {
-                writeln!(
-                    &mut self.writer,
-                    r"file:///{path}#{id} {module_name}.{identifier}",
-                    path = path.display(),
-                    id = item.id,
-                    identifier = item.identifier,
-                )
-                .with_context(|| format!("Failed to write entry {module_name}.{item}"))?;
+                let location = match &item.alias_of {
+                    // Aliases lead to the definition they rename, rendered next to this module.
+                    Some(alias) => format!(
+                        "file:///{path}",
+                        path = path.with_file_name(alias.target.to_string()).display()
+                    ),
+                    None => format!("file:///{path}#{id}", path = path.display(), id = item.id),
+                };
+                write_entry(&mut self.writer, location, &module_name, &item)?;
             }
         }
 
         Ok(())
     }
 }
+
+/// Write a line describing an item, the way the plaintext output does.
+///
+/// The line consists of space-separated fields: the item's `location`, its
+/// qualified name, its qualified name spelled in the Agda input method,
+/// and free text with the item's search aliases, the definition it is an
+/// alias of and its type signature.
+pub(super) fn write_entry<W: Write>(
+    mut writer: W,
+    location: impl Display,
+    module_name: &str,
+    item: &Item,
+) -> Result<()> {
+    let name = item.qualified_name(module_name);
+    let input_method_name = item.input_method_name(module_name);
+
+    write!(
+        writer,
+        "{location} {name} {input_method_name}",
+        input_method_name = input_method_name.as_ref().unwrap_or(&name),
+    )
+    .and_then(|()| match item.aliases.as_slice() {
+        [] => Ok(()),
+        aliases => write!(writer, " ({})", aliases.join(", ")),
+    })
+    .and_then(|()| match &item.alias_of {
+        Some(alias) => write!(writer, " [alias of {}]", alias.name),
+        None => Ok(()),
+    })
+    .and_then(|()| match &item.signature {
+        Some(signature) => writeln!(writer, " : {signature}"),
+        None => writeln!(writer),
+    })
+    .with_context(|| format!("Failed to write entry {name}"))
+}
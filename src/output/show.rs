@@ -0,0 +1,57 @@
+use std::io::Write;
+
+use anyhow::{bail, Context, Result};
+
+use super::OutputWriter;
+use crate::pipeline::Output;
+
+/// Prints the source of the definitions of a given qualified name.
+#[derive(Debug)]
+pub struct ShowOutput<W> {
+    name: String,
+    html: bool,
+    writer: W,
+}
+
+impl<W> ShowOutput<W> {
+    pub fn new(name: String, html: bool, writer: W) -> Self {
+        Self { name, html, writer }
+    }
+}
+
+impl<W> OutputWriter for ShowOutput<W>
+where
+    W: Write,
+{
+    fn write_output(&mut self, output: Output) -> Result<()> {
+        let mut modules: Vec<_> = output.into_iter().collect();
+        modules.sort_by(|a, b| a.module.name.cmp(&b.module.name));
+
+        let mut found = false;
+        for item in &modules {
+            let module = &item.module;
+
+            let snippets = module
+                .items
+                .iter()
+                .filter(|item| item.qualified_name(&module.name) == self.name)
+                .filter_map(|item| item.snippet.as_ref());
+            for snippet in snippets {
+                let source = if self.html {
+                    &snippet.html
+                } else {
+                    &snippet.text
+                };
+                writeln!(&mut self.writer, "{source}")
+                    .with_context(|| format!("Failed to write source of {}", self.name))?;
+                found = true;
+            }
+        }
+
+        if !found {
+            bail!("No definition named {}", self.name);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use std::io::Write;
+
+use anyhow::{Context, Result};
+
+use super::{definitions_named, OutputWriter};
+use crate::pipeline::Output;
+
+/// Lists the definitions that refer to any definition of a given name.
+#[derive(Debug)]
+pub struct UsesOutput<W> {
+    name: String,
+    writer: W,
+}
+
+impl<W> UsesOutput<W> {
+    pub fn new(name: String, writer: W) -> Self {
+        Self { name, writer }
+    }
+}
+
+impl<W> OutputWriter for UsesOutput<W>
+where
+    W: Write,
+{
+    fn write_output(&mut self, output: Output) -> Result<()> {
+        let mut modules: Vec<_> = output.into_iter().collect();
+        modules.sort_by(|a, b| a.module.name.cmp(&b.module.name));
+
+        let targets = definitions_named(&modules, &self.name)?;
+
+        for item in &modules {
+            let path = item.canonical_rendered_path()?;
+            let module = &item.module;
+
+            for user in &module.items {
+                if !user
+                    .references
+                    .iter()
+                    .any(|target| targets.contains(target))
+                {
+                    continue;
+                }
+
+                writeln!(
+                    &mut self.writer,
+                    r"file:///{path}#{id} {name}",
+                    path = path.display(),
+                    id = user.id,
+                    name = user.qualified_name(&module.name),
+                )
+                .with_context(|| format!("Failed to write user {}.{user}", module.name))?;
+            }
+        }
+
+        Ok(())
+    }
+}
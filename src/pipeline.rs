@@ -1,12 +1,14 @@
 use std::{
-    path::PathBuf,
-    sync::mpsc,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{mpsc, Arc},
 };
 
 use anyhow::{Context, Result};
 use threadpool::ThreadPool;
 
-use crate::module::{Module, ModuleParser};
+use crate::module::{ItemKind, Module, ModuleParser, Visibility};
 
 #[derive(Debug)]
 pub struct Item {
@@ -14,29 +16,164 @@ pub struct Item {
     pub module: Module,
 }
 
+impl Item {
+    /// Whether the module was rendered to Markdown, e.g. by `--html-highlight=code`.
+    pub fn is_markdown(&self) -> bool {
+        is_markdown(&self.source_path)
+    }
+
+    /// Path of the module's page on the final rendered site.
+    ///
+    /// Modules rendered to Markdown are turned into HTML pages of the same name
+    /// by whatever processes the Markdown, which is also where Agda links them.
+    pub fn rendered_path(&self) -> PathBuf {
+        self.source_path.with_extension("html")
+    }
+
+    /// Absolute path of the module's page on the final rendered site.
+    pub fn canonical_rendered_path(&self) -> Result<PathBuf> {
+        let path = std::fs::canonicalize(&self.source_path).with_context(|| {
+            format!("Failed to canonicalize path {}", self.source_path.display())
+        })?;
+        Ok(path.with_extension("html"))
+    }
+}
+
+/// How to index definitions local to a `where` block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LocalDefinitions {
+    /// Index them like any other definition of their module.
+    #[default]
+    Keep,
+    /// Leave them out of the index.
+    Hide,
+    /// Qualify them by the definition they are local to, as in `parent.helper`.
+    Qualify,
+}
+
+impl FromStr for LocalDefinitions {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keep" => Ok(Self::Keep),
+            "hide" => Ok(Self::Hide),
+            "qualify" => Ok(Self::Qualify),
+            _ => Err("expected one of 'keep', 'hide' or 'qualify'"),
+        }
+    }
+}
+
+/// Criteria deciding which items of a module end up in the index.
+#[derive(Debug, Default)]
+pub struct Filter {
+    /// Only keep items of these kinds, or items of any kind if empty.
+    pub kinds: Vec<ItemKind>,
+    /// Drop items declared in `private` blocks.
+    pub exclude_private: bool,
+    /// Drop items deprecated by `WARNING_ON_USAGE` pragmas.
+    pub exclude_deprecated: bool,
+    pub local_definitions: LocalDefinitions,
+    /// Only keep modules that enable all of these options, and may be imported
+    /// by modules using just these options.
+    pub options: Vec<String>,
+}
+
+impl Filter {
+    fn accepts(&self, module: &Module) -> bool {
+        if self.options.is_empty() {
+            return true;
+        }
+
+        self.options.iter().all(|option| module.has_option(option))
+            && module
+                .infective_options()
+                .all(|option| self.options.iter().any(|requested| requested == option))
+    }
+
+    fn apply(&self, module: &mut Module) {
+        if self.local_definitions == LocalDefinitions::Qualify {
+            Self::qualify_local_definitions(module);
+        }
+
+        module.items.retain(|item| {
+            (self.kinds.is_empty() || self.kinds.contains(&item.kind))
+                && !(self.exclude_private && item.visibility == Visibility::Private)
+                && !(self.exclude_deprecated && item.deprecation.is_some())
+                && !(self.local_definitions == LocalDefinitions::Hide && item.parent.is_some())
+        });
+    }
+
+    /// Add the names of the definitions each local definition belongs to to its scope.
+    fn qualify_local_definitions(module: &mut Module) {
+        let parents: HashMap<String, (String, Option<String>)> = module
+            .items
+            .iter()
+            .map(|item| {
+                (
+                    item.id.clone(),
+                    (item.identifier.clone(), item.parent.clone()),
+                )
+            })
+            .collect();
+
+        for item in &mut module.items {
+            let mut names = Vec::new();
+            let mut parent = item.parent.as_ref();
+            while let Some((identifier, next)) = parent.and_then(|id| parents.get(id)) {
+                names.push(identifier.clone());
+                parent = next.as_ref();
+            }
+            item.scope.extend(names.into_iter().rev());
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Pipeline {
     pool: ThreadPool,
+    filter: Arc<Filter>,
+    snippets: bool,
     tx: mpsc::Sender<Item>,
     rx: mpsc::Receiver<Item>,
 }
 
 impl Pipeline {
-    pub fn new() -> Self {
+    pub fn new(filter: Filter) -> Self {
         let name = "agda-index-module-worker".into();
         let pool = threadpool::Builder::new().thread_name(name).build();
 
         let (tx, rx) = mpsc::channel();
-        Self { pool, tx, rx }
+        Self {
+            pool,
+            filter: Arc::new(filter),
+            snippets: false,
+            tx,
+            rx,
+        }
+    }
+
+    /// Also extract the source of each definition, for outputs that show it.
+    pub fn with_snippets(mut self, snippets: bool) -> Self {
+        self.snippets = snippets;
+        self
     }
 
     pub fn process_module(&self, source_path: PathBuf) {
         let tx = self.tx.clone();
-        self.pool
-            .execute(move || match process_module(source_path) {
+        let filter = Arc::clone(&self.filter);
+        let snippets = self.snippets;
+        self.pool.execute(
+            move || match process_module(source_path, &filter, snippets) {
                 Err(err) => eprintln!("Failed to process module: {err}"),
-                Ok(item) => tx.send(item).context("Failed to send result for module")?,
-            });
+                Ok(None) => {}
+                Ok(Some(item)) => {
+                    if let Err(err) = tx.send(item) {
+                        eprintln!("Failed to send result for module: {err}")
+                    }
+                }
+            },
+        );
     }
 
     pub fn consume(self) -> Output {
@@ -44,19 +181,28 @@ impl Pipeline {
     }
 }
 
-fn process_module(source_path: PathBuf) -> Result<Item> {
-    let parser = ModuleParser::new();
+/// Whether the module at `path` was rendered to Markdown instead of HTML.
+fn is_markdown(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md")
+}
+
+fn process_module(source_path: PathBuf, filter: &Filter, snippets: bool) -> Result<Option<Item>> {
+    let parser = ModuleParser::new().with_snippets(snippets);
 
     let content = std::fs::read_to_string(&source_path)
         .with_context(|| format!("Failed to read module file at {}", source_path.display()))?;
-    let module = parser
-        .parse_module(&content)
+    let mut module = parser
+        .parse_module(&content, is_markdown(&source_path))
         .with_context(|| format!("Failed to parse module {}", source_path.display()))?;
+    if !filter.accepts(&module) {
+        return Ok(None);
+    }
+    filter.apply(&mut module);
 
-    Ok(Item {
+    Ok(Some(Item {
         source_path,
         module,
-    })
+    }))
 }
 
 pub struct Output {